@@ -0,0 +1,121 @@
+#import bevy_pbr::mesh_view_bindings
+#import bevy_pbr::mesh_bindings
+#import bevy_pbr::mesh_functions
+
+#import bevy_pbr::pbr_types
+#import bevy_pbr::utils
+#import bevy_pbr::clustered_forward
+#import bevy_pbr::lighting
+#import bevy_pbr::shadows
+#import bevy_pbr::pbr_functions
+
+struct CornMaterial {
+    color: vec4<f32>,
+};
+
+struct Time {
+    time: f32,
+};
+
+struct Sway {
+    strength: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> material: CornMaterial;
+@group(1) @binding(1)
+var<uniform> time: Time;
+@group(1) @binding(2)
+var<uniform> sway: Sway;
+
+let TAU: f32 = 6.28318530717958647692528676655900577;
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+#ifdef VERTEX_UVS
+    @location(2) uv: vec2<f32>,
+#endif
+#ifdef VERTEX_TANGENTS
+    @location(3) tangent: vec4<f32>,
+#endif
+#ifdef VERTEX_COLORS
+    @location(4) color: vec4<f32>,
+#endif
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    #import bevy_pbr::mesh_vertex_output
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    var world_position = mesh_position_local_to_world(mesh.model, vec4<f32>(vertex.position, 1.0));
+
+    // each stalk gets its own phase so the field doesn't move in lockstep
+    var phase = 0.0;
+#ifdef VERTEX_COLORS
+    phase = vertex.color.r * TAU;
+    out.color = vertex.color;
+#endif
+
+    // the base stays put and the tip moves the most
+    let height = max(vertex.position.y, 0.0);
+    let bend = height * height * sway.strength;
+    world_position.x = world_position.x + sin(time.time * 1.3 + phase) * bend;
+    world_position.z = world_position.z + cos(time.time * 0.9 + phase) * bend * 0.5;
+
+    out.world_position = world_position;
+    out.world_normal = mesh_normal_local_to_world(vertex.normal);
+#ifdef VERTEX_UVS
+    out.uv = vertex.uv;
+#endif
+#ifdef VERTEX_TANGENTS
+    out.world_tangent = mesh_tangent_local_to_world(mesh.model, vertex.tangent);
+#endif
+    out.clip_position = mesh_position_world_to_clip(out.world_position);
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(front_facing) is_front: bool,
+    @builtin(position) frag_coord: vec4<f32>,
+    #import bevy_pbr::mesh_vertex_output
+};
+
+@fragment
+fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
+    var pbr_input: PbrInput = pbr_input_new();
+
+    pbr_input.material.base_color = material.color;
+#ifdef VERTEX_COLORS
+    // vary the shade a little between stalks
+    pbr_input.material.base_color = vec4(material.color.rgb * (0.8 + 0.2 * in.color.g), material.color.a);
+#endif
+    pbr_input.material.perceptual_roughness = 0.9;
+
+    pbr_input.frag_coord = in.frag_coord;
+    pbr_input.world_position = in.world_position;
+    pbr_input.world_normal = in.world_normal;
+
+    pbr_input.is_orthographic = view.projection[3].w == 1.0;
+
+    pbr_input.N = prepare_normal(
+        pbr_input.material.flags,
+        in.world_normal,
+#ifdef VERTEX_TANGENTS
+#ifdef STANDARDMATERIAL_NORMAL_MAP
+        in.world_tangent,
+#endif
+#endif
+#ifdef VERTEX_UVS
+        in.uv,
+#endif
+        in.is_front,
+    );
+    pbr_input.V = calculate_view(in.world_position, pbr_input.is_orthographic);
+
+    return tone_mapping(pbr(pbr_input));
+}
@@ -1,4 +1,6 @@
-use crate::{assets::GameAssets, game_state, ingame, splash, title_screen, AppState};
+use crate::{
+    assets::GameAssets, game_state, ingame, maze_file, rounds, splash, title_screen, AppState,
+};
 use bevy::{asset::Asset, ecs::system::SystemParam, gltf::Gltf, prelude::*};
 use bevy_kira_audio::AudioSource;
 use std::marker::PhantomData;
@@ -45,6 +47,7 @@ pub struct AssetsHandler<'w, 's> {
     pub images: ResMut<'w, Assets<Image>>,
     state: ResMut<'w, State<AppState>>,
     next_state: ResMut<'w, NextState>,
+    pub rounds: Res<'w, rounds::Rounds>,
 
     #[system_param(ignore)]
     phantom: PhantomData<&'s ()>,
@@ -85,6 +88,18 @@ impl<'w, 's> AssetsHandler<'w, 's> {
         self.add_asset(glb, path);
     }
 
+    pub fn add_maze_file(&mut self, maze_file: &mut Handle<maze_file::MazeFile>, path: &str) {
+        self.add_asset(maze_file, path);
+    }
+
+    pub fn add_round_definitions(
+        &mut self,
+        round_definitions: &mut Handle<rounds::RoundDefinitions>,
+        path: &str,
+    ) {
+        self.add_asset(round_definitions, path);
+    }
+
     pub fn add_animation(&mut self, animation: &mut Handle<AnimationClip>, path: &str) {
         self.add_asset(animation, path);
     }
@@ -1,4 +1,4 @@
-use crate::asset_loading;
+use crate::{asset_loading, maze_file, rounds};
 use bevy::gltf::Gltf;
 use bevy::prelude::*;
 use bevy_kira_audio::AudioSource;
@@ -20,10 +20,11 @@ pub struct GameAssets {
     pub person_run: Handle<AnimationClip>,
     pub person_idle: Handle<AnimationClip>,
     pub maze: Handle<Gltf>,
+    pub maze_file: Handle<maze_file::MazeFile>,
+    pub rounds: Handle<rounds::RoundDefinitions>,
     pub football: Handle<Gltf>,
     pub corn_stalk: Handle<Gltf>,
     pub corn_stalk_material: Handle<StandardMaterial>,
-    pub corn_sway: Handle<AnimationClip>,
     pub combine: Handle<Gltf>,
     pub combine_drive: Handle<AnimationClip>,
 
@@ -51,6 +52,16 @@ pub struct GameAssets {
     pub blood: asset_loading::GameTexture,
     pub blood_mesh: Handle<Mesh>,
 
+    pub particle_mesh: Handle<Mesh>,
+    pub chaff_material: Handle<StandardMaterial>,
+    pub dust_material: Handle<StandardMaterial>,
+    pub smoke_material: Handle<StandardMaterial>,
+    pub wheel_rut_material: Handle<StandardMaterial>,
+    pub hay_bale_material: Handle<StandardMaterial>,
+    pub landing_marker_mesh: Handle<Mesh>,
+    pub landing_marker_material: Handle<StandardMaterial>,
+    pub confetti_materials: Vec<Handle<StandardMaterial>>,
+
     pub bill_person: Handle<Gltf>,
     pub will_person: Handle<Gltf>,
     pub host_idle: Handle<AnimationClip>,
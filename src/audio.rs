@@ -8,6 +8,7 @@ impl Plugin for GameAudioPlugin {
         app.add_audio_channel::<MusicChannel>()
             .add_audio_channel::<SoundChannel>()
             .add_audio_channel::<TalkChannel>()
+            .add_audio_channel::<WarningChannel>()
             .add_plugin(AudioPlugin);
     }
 }
@@ -15,12 +16,14 @@ impl Plugin for GameAudioPlugin {
 pub struct MusicChannel;
 pub struct SoundChannel;
 pub struct TalkChannel;
+pub struct WarningChannel;
 
 #[derive(SystemParam)]
 pub struct GameAudio<'w, 's> {
     music_channel: Res<'w, AudioChannel<MusicChannel>>,
     sound_channel: Res<'w, AudioChannel<SoundChannel>>,
     talk_channel: Res<'w, AudioChannel<TalkChannel>>,
+    warning_channel: Res<'w, AudioChannel<WarningChannel>>,
 
     #[system_param(ignore)]
     phantom: PhantomData<&'s ()>,
@@ -30,6 +33,7 @@ impl<'w, 's> GameAudio<'w, 's> {
     pub fn set_volume(&mut self) {
         self.sound_channel.set_volume(0.2);
         self.talk_channel.set_volume(0.2);
+        self.warning_channel.set_volume(0.3);
         self.music_channel.set_volume(0.5);
     }
     pub fn play_bgm(&mut self, handle: &Handle<AudioSource>) {
@@ -49,7 +53,19 @@ impl<'w, 's> GameAudio<'w, 's> {
     pub fn play_sfx(&mut self, handle: &Handle<AudioSource>) {
         self.sound_channel.play(handle.clone());
     }
+    pub fn play_sfx_pitched(&mut self, handle: &Handle<AudioSource>, playback_rate: f64) {
+        self.sound_channel
+            .play(handle.clone())
+            .with_playback_rate(playback_rate);
+    }
     pub fn play_talk(&mut self, handle: &Handle<AudioSource>) {
         self.talk_channel.play(handle.clone());
     }
+
+    pub fn play_warning(&mut self, handle: &Handle<AudioSource>, volume: f64, playback_rate: f64) {
+        self.warning_channel
+            .play(handle.clone())
+            .with_volume(volume)
+            .with_playback_rate(playback_rate);
+    }
 }
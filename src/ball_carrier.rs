@@ -0,0 +1,229 @@
+use crate::{
+    assets::GameAssets, audio::GameAudio, banter, collision, enemy, field, football, game_state,
+    ingame_ui, maze_validation, particles, pathing, player, stats, AppState,
+};
+use bevy::prelude::*;
+
+/// Lets enemies scoop up loose footballs and run them back into the end zone the player is
+/// defending. Running into the carrier takes the ball back.
+pub struct BallCarrierPlugin;
+impl Plugin for BallCarrierPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CarrierRoute::default()).add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(recover_loose_footballs)
+                .with_system(update_carrier_route)
+                .with_system(strip_ball_carriers)
+                .with_system(check_for_enemy_touchdown),
+        );
+    }
+}
+
+// carriers are a little slower than a chase so the player can catch up
+pub const CARRY_SPEED: f32 = 0.85;
+// the corn keeps changing, so the way to the end zone gets worked out again this often
+const ROUTE_REFRESH: f32 = 1.0;
+
+/// The way back to the end zone the player is defending, shared by every enemy carrying a ball.
+#[derive(Default)]
+pub struct CarrierRoute {
+    route: Option<pathing::Route>,
+    toward_left: bool,
+    refresh_cooldown: f32,
+}
+
+impl CarrierRoute {
+    pub fn next_waypoint(&self, position: Vec3) -> Option<Vec3> {
+        self.route
+            .as_ref()
+            .and_then(|route| route.next_waypoint(position))
+    }
+}
+
+/// Enemies score in the end zone the player is running away from.
+fn enemy_goal_is_left(game_state: &game_state::GameState) -> bool {
+    !game_state.touchdown_on_leftside
+}
+
+fn update_carrier_route(
+    mut carrier_route: ResMut<CarrierRoute>,
+    enemies: Query<&enemy::Enemy>,
+    collidables: collision::Collidables,
+    field: Res<field::FieldLayout>,
+    game_state: Res<game_state::GameState>,
+    time: Res<Time>,
+) {
+    if !enemies.iter().any(|enemy| enemy.carried_football.is_some()) {
+        carrier_route.route = None;
+        return;
+    }
+
+    let toward_left = enemy_goal_is_left(&game_state);
+    carrier_route.refresh_cooldown -= time.delta_seconds();
+    if carrier_route.route.is_some()
+        && carrier_route.toward_left == toward_left
+        && carrier_route.refresh_cooldown > 0.0
+    {
+        return;
+    }
+
+    let grid = maze_validation::WalkableGrid::new(&collidables.obstacles(), &field);
+    let route = pathing::Route::new(grid, |center| {
+        if toward_left {
+            center.z <= field.left_goal
+        } else {
+            center.z >= field.right_goal
+        }
+    });
+
+    carrier_route.route = Some(route);
+    carrier_route.toward_left = toward_left;
+    carrier_route.refresh_cooldown = ROUTE_REFRESH;
+}
+
+fn recover_loose_footballs(
+    mut commands: Commands,
+    mut enemies: Query<(Entity, &mut enemy::Enemy, &Transform)>,
+    footballs: Query<(Entity, &football::Football, &Transform), Without<enemy::Enemy>>,
+    players: Query<(&player::Player, &Transform), Without<enemy::Enemy>>,
+    mut carried_footballs: Query<(&football::CarriedFootball, &mut Visibility, &Parent)>,
+    game_assets: Res<GameAssets>,
+    mut audio: GameAudio,
+    mut textbox_event_writer: EventWriter<ingame_ui::SetTextBoxEvent>,
+) {
+    for (football_entity, football, football_transform) in &footballs {
+        if !football.has_landed {
+            continue;
+        }
+
+        // the player gets it if they're both right there
+        let player_can_reach = players.iter().any(|(player, player_transform)| {
+            !player.has_football
+                && football::within_reach(
+                    football_transform.translation,
+                    player_transform.translation,
+                )
+        });
+        if player_can_reach {
+            continue;
+        }
+
+        let recovered_by = enemies.iter_mut().find(|(_, enemy, enemy_transform)| {
+            enemy.carried_football.is_none()
+                && !enemy.is_launched
+                && !enemy.is_attached
+                && !enemy.has_dived
+                && football::within_reach(
+                    football_transform.translation,
+                    enemy_transform.translation,
+                )
+        });
+
+        if let Some((enemy_entity, mut enemy, _)) = recovered_by {
+            enemy.carried_football = Some(football.kind);
+            football::show_carried_football(&mut carried_footballs, enemy_entity, true);
+            commands.entity(football_entity).despawn_recursive();
+            audio.play_sfx(&game_assets.football_pop);
+            textbox_event_writer.send(ingame_ui::SetTextBoxEvent {
+                texts: vec![
+                    banter::bill_talk("The defense has picked up the ball!", &game_assets),
+                    banter::will_talk("Somebody stop them before they score!", &game_assets),
+                ],
+            });
+        }
+    }
+}
+
+fn strip_ball_carriers(
+    mut enemies: Query<(Entity, &mut enemy::Enemy, &Transform)>,
+    mut players: Query<(Entity, &mut player::Player, &Transform), Without<enemy::Enemy>>,
+    mut carried_footballs: Query<(&football::CarriedFootball, &mut Visibility, &Parent)>,
+    game_assets: Res<GameAssets>,
+    mut audio: GameAudio,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    mut stat_event_writer: EventWriter<stats::StatEvent>,
+) {
+    for (player_entity, mut player, player_transform) in &mut players {
+        if player.has_football || player.is_dead || player.is_tackled {
+            continue;
+        }
+
+        for (enemy_entity, mut enemy, enemy_transform) in &mut enemies {
+            if enemy.is_launched
+                || !football::within_reach(
+                    enemy_transform.translation,
+                    player_transform.translation,
+                )
+            {
+                continue;
+            }
+
+            if let Some(kind) = enemy.carried_football.take() {
+                player.has_football = true;
+                player.carried_football = kind;
+                football::show_carried_football(&mut carried_footballs, enemy_entity, false);
+                football::show_carried_football(&mut carried_footballs, player_entity, true);
+                stat_event_writer.send(stats::StatEvent::Pickup);
+                audio.play_sfx(&game_assets.tackle_sound);
+                spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                    particles::ParticleKind::Dust,
+                    enemy_transform.translation,
+                ));
+                break;
+            }
+        }
+    }
+}
+
+fn check_for_enemy_touchdown(
+    mut enemies: Query<(Entity, &mut enemy::Enemy, &Transform)>,
+    mut carried_footballs: Query<(&football::CarriedFootball, &mut Visibility, &Parent)>,
+    mut game_state: ResMut<game_state::GameState>,
+    field: Res<field::FieldLayout>,
+    game_assets: Res<GameAssets>,
+    mut audio: GameAudio,
+    mut football_launch_event_writer: EventWriter<football::LaunchFootballEvent>,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    mut textbox_event_writer: EventWriter<ingame_ui::SetTextBoxEvent>,
+) {
+    let toward_left = enemy_goal_is_left(&game_state);
+    for (enemy_entity, mut enemy, enemy_transform) in &mut enemies {
+        let in_end_zone = if toward_left {
+            enemy_transform.translation.z <= field.left_goal
+        } else {
+            enemy_transform.translation.z >= field.right_goal
+        };
+        if !in_end_zone {
+            continue;
+        }
+
+        let kind = match enemy.carried_football.take() {
+            Some(kind) => kind,
+            None => continue,
+        };
+
+        game_state.score = game_state.score.saturating_sub(kind.points());
+        football::show_carried_football(&mut carried_footballs, enemy_entity, false);
+        audio.play_sfx(&game_assets.touch_down);
+        spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+            particles::ParticleKind::Dust,
+            enemy_transform.translation,
+        ));
+        textbox_event_writer.send(ingame_ui::SetTextBoxEvent {
+            texts: vec![
+                banter::will_talk("Oh no, the defense ran it all the way back!", &game_assets),
+                banter::bill_talk(
+                    &format!("That costs {} points.", kind.points()),
+                    &game_assets,
+                ),
+            ],
+        });
+
+        // regular balls get kicked back out, bonus balls are just gone
+        if kind == football::FootballKind::Regular {
+            football_launch_event_writer.send(football::LaunchFootballEvent::new(
+                football::FootballKind::Regular,
+            ));
+        }
+    }
+}
@@ -283,7 +283,7 @@ fn generate_banter(game_assets: &GameAssets) -> Vec<Banter> {
     ]
 }
 
-fn will_talk(text: &str, game_assets: &GameAssets) -> ingame_ui::TextBoxText {
+pub fn will_talk(text: &str, game_assets: &GameAssets) -> ingame_ui::TextBoxText {
     ingame_ui::TextBoxText {
         text: text.to_string(),
         speed: 0.3,
@@ -302,7 +302,7 @@ fn will_silent(text: &str, game_assets: &GameAssets) -> ingame_ui::TextBoxText {
     }
 }
 
-fn bill_talk(text: &str, game_assets: &GameAssets) -> ingame_ui::TextBoxText {
+pub fn bill_talk(text: &str, game_assets: &GameAssets) -> ingame_ui::TextBoxText {
     ingame_ui::TextBoxText {
         text: text.to_string(),
         speed: 0.3,
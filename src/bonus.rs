@@ -0,0 +1,295 @@
+use crate::{
+    assets::GameAssets, banter, enemy, football, game_state, ingame, ingame_ui, menus, player,
+    rounds, ui::text_size, AppState,
+};
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use serde::Deserialize;
+
+/// Every so often a round throws in something extra: a golden football hidden deep in the corn
+/// or a bunch of extra footballs kicked in at once.
+pub struct BonusPlugin;
+impl Plugin for BonusPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BonusState::default())
+            .add_system_set(SystemSet::on_exit(AppState::ResetInGame).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(start_bonuses)
+                    .with_system(expire_golden_footballs)
+                    .with_system(announce_bonus_touchdowns)
+                    .with_system(update_bonus_text),
+            );
+    }
+}
+
+#[derive(Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BonusKind {
+    Golden,
+    MultiBall,
+}
+
+/// Controls how often bonus footballs show up during a round and what they look like.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct BonusSettings {
+    pub enabled: bool,
+    pub min_interval: f32,
+    pub max_interval: f32,
+    pub golden_time: f32,
+    pub multi_ball_count: usize,
+    pub kinds: Vec<BonusKind>,
+}
+
+impl BonusSettings {
+    fn next_interval(&self) -> f32 {
+        if self.max_interval > self.min_interval {
+            thread_rng().gen_range(self.min_interval..self.max_interval)
+        } else {
+            self.min_interval
+        }
+    }
+}
+
+impl Default for BonusSettings {
+    fn default() -> Self {
+        BonusSettings {
+            enabled: true,
+            min_interval: 40.0,
+            max_interval: 60.0,
+            golden_time: 15.0,
+            multi_ball_count: 3,
+            kinds: vec![BonusKind::Golden, BonusKind::MultiBall],
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BonusState {
+    cooldown: Option<f32>,
+    golden_time_left: f32,
+}
+
+#[derive(Component)]
+struct BonusText;
+
+fn setup(
+    mut commands: Commands,
+    mut bonus_state: ResMut<BonusState>,
+    game_assets: Res<GameAssets>,
+    text_scaler: text_size::TextScaler,
+) {
+    *bonus_state = BonusState::default();
+
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: text_scaler.scale(menus::DEFAULT_FONT_SIZE),
+                    color: Color::rgb(1.0, 0.8, 0.2),
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Percent(2.0),
+                    left: Val::Percent(40.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        )
+        .insert(BonusText)
+        .insert(ingame::CleanupMarker)
+        .insert(ingame_ui::CleanupMarker);
+}
+
+/// Whether a bonus football is still out there or being carried.
+fn bonus_in_play(
+    footballs: &Query<&football::Football>,
+    players: &Query<&player::Player>,
+    enemies: &Query<&enemy::Enemy>,
+) -> bool {
+    footballs
+        .iter()
+        .any(|football| football.kind != football::FootballKind::Regular)
+        || players.iter().any(|player| {
+            player.has_football && player.carried_football != football::FootballKind::Regular
+        })
+        || enemies.iter().any(|enemy| {
+            enemy
+                .carried_football
+                .is_some_and(|kind| kind != football::FootballKind::Regular)
+        })
+}
+
+fn start_bonuses(
+    mut bonus_state: ResMut<BonusState>,
+    game_state: Res<game_state::GameState>,
+    rounds: Res<rounds::Rounds>,
+    footballs: Query<&football::Football>,
+    players: Query<&player::Player>,
+    enemies: Query<&enemy::Enemy>,
+    game_assets: Res<GameAssets>,
+    time: Res<Time>,
+    mut football_launch_event_writer: EventWriter<football::LaunchFootballEvent>,
+    mut textbox_event_writer: EventWriter<ingame_ui::SetTextBoxEvent>,
+) {
+    let settings = match rounds.get(game_state.current_round) {
+        Some(round) => &round.bonuses,
+        None => return,
+    };
+    // nothing until the first ball is out
+    if !settings.enabled || settings.kinds.is_empty() || !game_state.enemies_spawned {
+        return;
+    }
+
+    // one bonus at a time
+    if bonus_in_play(&footballs, &players, &enemies) {
+        return;
+    }
+
+    let cooldown = bonus_state
+        .cooldown
+        .get_or_insert_with(|| settings.next_interval());
+    *cooldown -= time.delta_seconds();
+    if *cooldown > 0.0 {
+        return;
+    }
+    bonus_state.cooldown = Some(settings.next_interval());
+
+    let kind = *settings
+        .kinds
+        .choose(&mut thread_rng())
+        .expect("checked that there are bonus kinds");
+
+    let texts = match kind {
+        BonusKind::Golden => {
+            bonus_state.golden_time_left = settings.golden_time;
+            football_launch_event_writer.send(football::LaunchFootballEvent::new(
+                football::FootballKind::Golden,
+            ));
+            vec![
+                banter::bill_talk("Is that a golden football out in the corn?", &game_assets),
+                banter::will_talk(
+                    &format!(
+                        "That's worth {} points if someone can find it in time!",
+                        football::GOLDEN_TOUCHDOWN_POINTS
+                    ),
+                    &game_assets,
+                ),
+            ]
+        }
+        BonusKind::MultiBall => {
+            for _ in 0..settings.multi_ball_count {
+                football_launch_event_writer.send(football::LaunchFootballEvent::new(
+                    football::FootballKind::MultiBall,
+                ));
+            }
+            vec![
+                banter::will_talk(
+                    "Wait, how many footballs are they kicking out there?",
+                    &game_assets,
+                ),
+                banter::bill_talk("It's multi-ball! Grab as many as you can!", &game_assets),
+            ]
+        }
+    };
+    textbox_event_writer.send(ingame_ui::SetTextBoxEvent { texts });
+}
+
+fn expire_golden_footballs(
+    mut commands: Commands,
+    mut bonus_state: ResMut<BonusState>,
+    footballs: Query<(Entity, &football::Football)>,
+    game_assets: Res<GameAssets>,
+    time: Res<Time>,
+    mut textbox_event_writer: EventWriter<ingame_ui::SetTextBoxEvent>,
+) {
+    let golden = footballs
+        .iter()
+        .filter(|(_, football)| football.kind == football::FootballKind::Golden)
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    if golden.is_empty() {
+        return;
+    }
+
+    bonus_state.golden_time_left -= time.delta_seconds();
+    if bonus_state.golden_time_left > 0.0 {
+        return;
+    }
+
+    for entity in golden {
+        commands.entity(entity).despawn_recursive();
+    }
+    textbox_event_writer.send(ingame_ui::SetTextBoxEvent {
+        texts: vec![
+            banter::will_talk("And the golden football is gone.", &game_assets),
+            banter::bill_talk("Maybe next time.", &game_assets),
+        ],
+    });
+}
+
+fn announce_bonus_touchdowns(
+    mut touchdown_event_reader: EventReader<game_state::TouchdownEvent>,
+    game_assets: Res<GameAssets>,
+    mut textbox_event_writer: EventWriter<ingame_ui::SetTextBoxEvent>,
+) {
+    for event in touchdown_event_reader.iter() {
+        if event.football == football::FootballKind::Golden {
+            textbox_event_writer.send(ingame_ui::SetTextBoxEvent {
+                texts: vec![
+                    banter::bill_talk("Golden touchdown!", &game_assets),
+                    banter::will_talk(
+                        &format!(
+                            "That's {} points on the board!",
+                            football::GOLDEN_TOUCHDOWN_POINTS
+                        ),
+                        &game_assets,
+                    ),
+                ],
+            });
+        }
+    }
+}
+
+fn update_bonus_text(
+    bonus_state: Res<BonusState>,
+    footballs: Query<&football::Football>,
+    players: Query<&player::Player>,
+    mut texts: Query<&mut Text, With<BonusText>>,
+) {
+    let carrying = players
+        .iter()
+        .find(|player| player.has_football)
+        .map(|player| player.carried_football);
+    let golden_out = footballs
+        .iter()
+        .any(|football| football.kind == football::FootballKind::Golden);
+    let multi_balls_out = footballs
+        .iter()
+        .filter(|football| football.kind == football::FootballKind::MultiBall)
+        .count();
+
+    let value = if carrying == Some(football::FootballKind::Golden) {
+        format!("GOLDEN BALL +{}", football::GOLDEN_TOUCHDOWN_POINTS)
+    } else if golden_out {
+        format!(
+            "GOLDEN BALL {}",
+            bonus_state.golden_time_left.max(0.0).ceil() as usize
+        )
+    } else if multi_balls_out > 0 || carrying == Some(football::FootballKind::MultiBall) {
+        format!("MULTI-BALL x{}", multi_balls_out)
+    } else {
+        String::new()
+    };
+
+    for mut text in &mut texts {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
@@ -1,8 +1,19 @@
-use crate::{BOTTOM_END, LEFT_END, RIGHT_END, TOP_END};
+use crate::{
+    enemy, field,
+    maze::{self, CornKind, CornStalk},
+    player,
+    spatial_grid::{self, SpatialGrid},
+    AppState,
+};
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::render::primitives::Aabb;
 
+pub mod geometry;
+
+use geometry::Obstacle;
+pub use geometry::WorldAabb;
+
 #[derive(Component)]
 pub struct Collidable {
     pub aabb: WorldAabb,
@@ -11,10 +22,81 @@ pub struct Collidable {
 #[derive(Component)]
 pub struct DynamicCollidable;
 
-#[derive(Debug, Default, Copy, Clone)]
-pub struct WorldAabb {
-    pub min: Vec3,
-    pub max: Vec3,
+// how far from their center actors bump into things
+pub const ACTOR_RADIUS: f32 = 0.3;
+// passes over every pair of actors each frame, more lets pushes carry through a crowd
+const SEPARATION_PASSES: usize = 2;
+
+/// Keeps the player and enemies from walking through each other.
+pub struct CollisionPlugin;
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::InGame).with_system(
+                separate_actors
+                    .after(player::move_player)
+                    .after(enemy::move_enemy),
+            ),
+        );
+    }
+}
+
+/// How much room an actor takes up and how hard it is to shove out of the way.
+#[derive(Component, Copy, Clone)]
+pub struct ActorBody {
+    pub radius: f32,
+    pub mass: f32,
+}
+
+fn separate_actors(
+    mut actors: Query<(Entity, &mut Transform, &ActorBody, Option<&enemy::Enemy>)>,
+    collidables: Collidables,
+) {
+    let mut bodies = actors
+        .iter()
+        .filter(|(_, _, _, enemy)| {
+            // attached enemies ride along with the player and launched ones are in the air
+            enemy.map_or(true, |enemy| !enemy.is_attached && !enemy.is_launched)
+        })
+        .map(|(entity, transform, body, _)| (entity, transform.translation, *body))
+        .collect::<Vec<_>>();
+    if bodies.len() < 2 {
+        return;
+    }
+
+    for _ in 0..SEPARATION_PASSES {
+        for i in 0..bodies.len() {
+            for j in (i + 1)..bodies.len() {
+                let (_, a, a_body) = bodies[i];
+                let (_, b, b_body) = bodies[j];
+                if let Some((a_push, b_push)) = geometry::separation(
+                    a,
+                    a_body.radius,
+                    a_body.mass,
+                    b,
+                    b_body.radius,
+                    b_body.mass,
+                ) {
+                    bodies[i].1 = a + a_push;
+                    bodies[j].1 = b + b_push;
+                }
+            }
+        }
+    }
+
+    for (entity, position, body) in bodies {
+        if let Ok((_, mut transform, _, _)) = actors.get_mut(entity) {
+            if transform.translation == position {
+                continue;
+            }
+
+            // don't shove anyone into the corn or off the field
+            let mut new = position;
+            let mut velocity = position - transform.translation;
+            collidables.fit_in(&transform.translation, &mut new, &mut velocity, body.radius);
+            transform.translation = new;
+        }
+    }
 }
 
 #[derive(SystemParam)]
@@ -22,188 +104,168 @@ pub struct Collidables<'w, 's> {
     collidables: Query<'w, 's, &'static Collidable>,
     dynamic_collidables: Query<'w, 's, (Entity, &'static DynamicCollidable)>,
     aabbs: Query<'w, 's, (&'static Aabb, &'static GlobalTransform)>,
+    corns: Query<'w, 's, &'static CornStalk>,
+    spatial_grid: Res<'w, SpatialGrid>,
+    field: Res<'w, field::FieldLayout>,
 }
 
 impl<'w, 's> Collidables<'w, 's> {
     pub fn is_in_collidable(&self, position: &Vec3) -> bool {
-        if position.z <= LEFT_END
-            || position.z >= RIGHT_END
-            || position.x <= BOTTOM_END
-            || position.x >= TOP_END
-        {
+        if geometry::is_out_of_bounds(*position, &self.field.bounds()) {
             return true;
         }
 
-        if self.collidables.iter().count() == 0 && self.dynamic_collidables.iter().count() == 0 {
+        if self.collidables.is_empty() && self.dynamic_collidables.is_empty() {
             return false;
         }
 
-        for collidable in self.collidables.iter() {
-            if position.x <= collidable.aabb.max.x
-                && position.x >= collidable.aabb.min.x
-                && position.z <= collidable.aabb.max.z
-                && position.z >= collidable.aabb.min.z
-            {
-                return true;
-            }
-        }
+        self.collidables_at(position)
+            .iter()
+            .any(|collidable| collidable.aabb.contains(*position))
+            || self
+                .dynamic_obstacles()
+                .iter()
+                .any(|obstacle| obstacle.contains(*position))
+    }
 
-        let dynamic_collidables = self
-            .dynamic_collidables
+    /// Every box on the field in one list, with the ones that move around grown to the world
+    /// space box around them. For when a whole area needs checking at once.
+    pub fn obstacles(&self) -> Vec<WorldAabb> {
+        let mut obstacles = self
+            .collidables
             .iter()
-            .filter_map(|(entity, _)| self.aabbs.get(entity).ok());
-
-        for (aabb, global_transform) in dynamic_collidables {
-            let matrix = global_transform.compute_matrix();
-            let inverse_matrix = matrix.inverse();
-            let min = aabb.min();
-            let max = aabb.max();
-
-            let transformed_new = inverse_matrix.transform_point3(*position);
-
-            if transformed_new.x <= max.x
-                && transformed_new.x >= min.x
-                && transformed_new.z <= max.z
-                && transformed_new.z >= min.z
-            {
-                return true;
-            }
-        }
+            .map(|collidable| collidable.aabb)
+            .collect::<Vec<_>>();
+        obstacles.extend(
+            self.dynamic_collidables
+                .iter()
+                .filter_map(|(entity, _)| self.aabbs.get(entity).ok())
+                .map(|(aabb, global_transform)| {
+                    let (min, max) = spatial_grid::world_bounds(
+                        &global_transform.compute_matrix(),
+                        aabb.min().into(),
+                        aabb.max().into(),
+                    );
+                    WorldAabb { min, max }
+                }),
+        );
+        obstacles
+    }
 
-        return false;
+    /// Oriented boxes for everything that moves around, like the combines.
+    fn dynamic_obstacles(&self) -> Vec<Obstacle> {
+        self.dynamic_collidables
+            .iter()
+            .filter_map(|(entity, _)| self.aabbs.get(entity).ok())
+            .map(|(aabb, global_transform)| {
+                Obstacle::Oriented(
+                    WorldAabb {
+                        min: aabb.min().into(),
+                        max: aabb.max().into(),
+                    },
+                    global_transform.compute_matrix(),
+                )
+            })
+            .collect()
     }
 
-    pub fn fit_in(&self, current: &Vec3, new: &mut Vec3, velocity: &mut Vec3, time: &Res<Time>) {
-        if self.collidables.iter().count() == 0 && self.dynamic_collidables.iter().count() == 0 {
-            return;
-        }
+    fn collidables_at(&self, position: &Vec3) -> Vec<&Collidable> {
+        self.spatial_grid
+            .collidables_at(*position)
+            .filter_map(|entity| self.collidables.get(entity).ok())
+            .collect()
+    }
 
-        if new.z <= LEFT_END {
-            *new = *current;
-            *velocity = Vec3::new(velocity.x, velocity.y, velocity.z.abs().max(1.0) * 2.0);
-            return;
-        }
-        if new.z >= RIGHT_END {
-            *new = *current;
-            *velocity = Vec3::new(
-                velocity.x,
-                velocity.y,
-                (velocity.z.abs() * -1.0).min(-1.0) * 2.0,
-            );
-            return;
-        }
-        if new.x <= BOTTOM_END {
-            *new = *current;
-            *velocity = Vec3::new(velocity.x.abs().max(1.0) * 2.0, velocity.y, velocity.z);
+    fn is_in_sparse_corn(&self, position: &Vec3) -> bool {
+        self.spatial_grid
+            .corn_near(*position, maze::CORN_THICKNESS)
+            .filter_map(|entity| self.corns.get(entity).ok())
+            .any(|corn| corn.kind == CornKind::Sparse)
+    }
+
+    /// Whether the position is inside corn the player could push their way through.
+    pub fn is_in_trampleable_corn(&self, position: &Vec3) -> bool {
+        self.spatial_grid
+            .corn_near(*position, maze::CORN_THICKNESS + ACTOR_RADIUS)
+            .filter_map(|entity| self.corns.get(entity).ok())
+            .any(|corn| corn.kind.is_trampleable())
+    }
+
+    /// How much corn is standing within distance of the position.
+    pub fn corn_count_near(&self, position: &Vec3, distance: f32) -> usize {
+        self.spatial_grid
+            .corn_near(*position, distance)
+            .filter(|entity| self.corns.get(*entity).is_ok())
+            .count()
+    }
+
+    /// Moves from current toward new as far as an actor of the given radius can go, sliding
+    /// along anything in the way. The field edges bounce actors back instead.
+    pub fn fit_in(&self, current: &Vec3, new: &mut Vec3, velocity: &mut Vec3, radius: f32) {
+        if self.collidables.is_empty() && self.dynamic_collidables.is_empty() {
             return;
         }
-        if new.x >= TOP_END {
+
+        if let Some(bounced) = geometry::bounce_off_bounds(*new, *velocity, &self.field.bounds()) {
             *new = *current;
-            *velocity = Vec3::new(
-                (velocity.x.abs() * -1.0).min(-1.0) * 2.0,
-                velocity.y,
-                velocity.z,
-            );
+            *velocity = bounced;
             return;
         }
 
-        let mut is_valid = true;
-        let mut current_aabbs = vec![];
-
-        for collidable in self.collidables.iter() {
-            if new.x <= collidable.aabb.max.x
-                && new.x >= collidable.aabb.min.x
-                && new.z <= collidable.aabb.max.z
-                && new.z >= collidable.aabb.min.z
-            {
-                is_valid = false;
-                current_aabbs.push(collidable.aabb);
-            }
+        // sparse corn doesn't stop anything, it's just slow going
+        if self.is_in_sparse_corn(new) {
+            *new = *current + ((*new - *current) * maze::SPARSE_CORN_SLOWDOWN);
         }
 
-        let dynamic_collidables = self
-            .dynamic_collidables
-            .iter()
-            .filter_map(|(entity, _)| self.aabbs.get(entity).ok());
-
-        for (aabb, global_transform) in dynamic_collidables {
-            let matrix = global_transform.compute_matrix();
-            let inverse_matrix = matrix.inverse();
-            let min = aabb.min();
-            let max = aabb.max();
-
-            let transformed_new = inverse_matrix.transform_point3(*new);
-
-            if transformed_new.x <= max.x
-                && transformed_new.x >= min.x
-                && transformed_new.z <= max.z
-                && transformed_new.z >= min.z
-            {
-                // bounce away I guess
-                *new = *current;
-                *velocity = Vec3::new(-velocity.x, -velocity.y, -velocity.z) * 2.0;
-                return;
-            }
-        }
+        let obstacles = self.obstacles_between(current, new, radius);
+        let (position, slid_velocity) =
+            geometry::slide(*current, *new - *current, *velocity, radius, &obstacles);
+        *new = Vec3::new(position.x, new.y, position.z);
+        *velocity = slid_velocity;
+    }
 
-        if is_valid {
+    /// Moves from current toward new, bouncing off of anything in the way and the field edges
+    /// rather than sliding along them. Used for things that fly around, like footballs.
+    pub fn deflect(
+        &self,
+        current: &Vec3,
+        new: &mut Vec3,
+        velocity: &mut Vec3,
+        radius: f32,
+        restitution: f32,
+    ) {
+        if let Some(bounced) =
+            geometry::reflect_off_bounds(*new, *velocity, restitution, &self.field.bounds())
+        {
+            *new = *current;
+            *velocity = bounced;
             return;
         }
 
-        let get_sign = |num: f32| {
-            let sign = num.signum();
-            if sign.is_nan() {
-                1.0
-            } else {
-                sign
-            }
-        };
-
-        let mut temp_new = *current;
-        for aabb in current_aabbs.iter() {
-            let top_normal = (Vec3::new(aabb.max.x, 0.0, aabb.min.z)
-                - Vec3::new(aabb.max.x, 0.0, aabb.max.z))
-            .cross(Vec3::Y)
-            .normalize();
-            let bottom_normal = (Vec3::new(aabb.min.x, 0.0, aabb.max.z)
-                - Vec3::new(aabb.min.x, 0.0, aabb.min.z))
-            .cross(Vec3::Y)
-            .normalize();
-            let left_normal = (Vec3::new(aabb.min.x, 0.0, aabb.min.z)
-                - Vec3::new(aabb.max.x, 0.0, aabb.min.z))
-            .cross(Vec3::Y)
-            .normalize();
-            let right_normal = (Vec3::new(aabb.max.x, 0.0, aabb.max.z)
-                - Vec3::new(aabb.min.x, 0.0, aabb.max.z))
-            .cross(Vec3::Y)
-            .normalize();
-
-            let normalized_current = (*new - temp_new).normalize();
-            if top_normal.dot(normalized_current) < 0.0 && temp_new.x > aabb.max.x {
-                let undesired =
-                    (velocity.x.abs().max(1.0) * top_normal) * normalized_current.dot(top_normal);
-                *new = temp_new - (undesired * time.delta_seconds());
-                velocity.x = 0.0;
-                velocity.z = velocity.z + (get_sign(velocity.z) * velocity.x);
-            } else if bottom_normal.dot(normalized_current) < 0.0 && temp_new.x < aabb.min.x {
-                let undesired = (velocity.x.abs().max(1.0) * bottom_normal)
-                    * normalized_current.dot(bottom_normal);
-                *new = temp_new - (undesired * time.delta_seconds());
-                velocity.x = 0.0;
-                velocity.z = velocity.z + (get_sign(velocity.z) * velocity.x);
-            } else if left_normal.dot(normalized_current) < 0.0 && temp_new.z < aabb.min.z {
-                let undesired =
-                    (velocity.z.abs().max(1.0) * left_normal) * normalized_current.dot(left_normal);
-                *new = temp_new - (undesired * time.delta_seconds());
-                velocity.x = velocity.x + (get_sign(velocity.x) * velocity.z);
-                velocity.z = 0.0;
-            } else if right_normal.dot(normalized_current) < 0.0 && temp_new.z > aabb.max.z {
-                let undesired = (velocity.z.abs().max(1.0) * right_normal)
-                    * normalized_current.dot(right_normal);
-                *new = temp_new - (undesired * time.delta_seconds());
-                velocity.x = velocity.x + (get_sign(velocity.x) * velocity.z);
-                velocity.z = 0.0;
-            }
-        }
+        let obstacles = self.obstacles_between(current, new, radius);
+        let (position, deflected_velocity) = geometry::deflect(
+            *current,
+            *new - *current,
+            *velocity,
+            radius,
+            restitution,
+            &obstacles,
+        );
+        *new = position;
+        *velocity = deflected_velocity;
+    }
+
+    /// Everything a circle could run into going from current to new.
+    fn obstacles_between(&self, current: &Vec3, new: &Vec3, radius: f32) -> Vec<Obstacle> {
+        let padding = Vec3::new(radius, 0.0, radius);
+        let mut obstacles = self
+            .spatial_grid
+            .collidables_between(current.min(*new) - padding, current.max(*new) + padding)
+            .into_iter()
+            .filter_map(|entity| self.collidables.get(entity).ok())
+            .map(|collidable| Obstacle::Aabb(collidable.aabb))
+            .collect::<Vec<_>>();
+        obstacles.extend(self.dynamic_obstacles());
+        obstacles
     }
 }
@@ -0,0 +1,1000 @@
+//! The math behind collisions, kept away from the ECS so it can be tested on its own. Everything
+//! happens on the x/z plane, y is ignored.
+
+use bevy::math::{Mat4, Vec3};
+
+// how many times a move can slide off of something before giving up on the rest of it
+const MAX_SLIDES: usize = 3;
+// keeps actors from ending up exactly on the surface they slid along
+const SKIN: f32 = 0.001;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct WorldAabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl WorldAabb {
+    /// Whether the position is inside the box or on its edge.
+    pub fn contains(&self, position: Vec3) -> bool {
+        position.x <= self.max.x
+            && position.x >= self.min.x
+            && position.z <= self.max.z
+            && position.z >= self.min.z
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Hit {
+    // how far along the movement, from 0.0 to 1.0
+    pub time: f32,
+    pub normal: Vec3,
+}
+
+/// Something actors can't move through. Oriented boxes are in the local space of their matrix,
+/// like the Aabb of a rotated mesh.
+#[derive(Debug, Copy, Clone)]
+pub enum Obstacle {
+    Aabb(WorldAabb),
+    Oriented(WorldAabb, Mat4),
+}
+
+impl Obstacle {
+    pub fn contains(&self, position: Vec3) -> bool {
+        match self {
+            Obstacle::Aabb(aabb) => aabb.contains(position),
+            Obstacle::Oriented(aabb, matrix) => {
+                aabb.contains(matrix.inverse().transform_point3(position))
+            }
+        }
+    }
+
+    pub fn sweep(&self, start: Vec3, movement: Vec3, radius: f32) -> Option<Hit> {
+        match self {
+            Obstacle::Aabb(aabb) => sweep_circle_aabb(start, movement, radius, aabb),
+            Obstacle::Oriented(aabb, matrix) => {
+                sweep_circle_oriented_aabb(start, movement, radius, aabb, matrix)
+            }
+        }
+    }
+}
+
+/// Whether the position is on or past an edge of the bounds.
+pub fn is_out_of_bounds(position: Vec3, bounds: &WorldAabb) -> bool {
+    position.z <= bounds.min.z
+        || position.z >= bounds.max.z
+        || position.x <= bounds.min.x
+        || position.x >= bounds.max.x
+}
+
+/// The velocity an actor gets knocked back with when it tries to move out of bounds, at least
+/// twice its speed into the edge and never less than 2.0.
+pub fn bounce_off_bounds(new: Vec3, velocity: Vec3, bounds: &WorldAabb) -> Option<Vec3> {
+    if new.z <= bounds.min.z {
+        Some(Vec3::new(
+            velocity.x,
+            velocity.y,
+            velocity.z.abs().max(1.0) * 2.0,
+        ))
+    } else if new.z >= bounds.max.z {
+        Some(Vec3::new(
+            velocity.x,
+            velocity.y,
+            (-velocity.z.abs()).min(-1.0) * 2.0,
+        ))
+    } else if new.x <= bounds.min.x {
+        Some(Vec3::new(
+            velocity.x.abs().max(1.0) * 2.0,
+            velocity.y,
+            velocity.z,
+        ))
+    } else if new.x >= bounds.max.x {
+        Some(Vec3::new(
+            (-velocity.x.abs()).min(-1.0) * 2.0,
+            velocity.y,
+            velocity.z,
+        ))
+    } else {
+        None
+    }
+}
+
+/// Moves a circle from start along movement as far as it can go, sliding along anything in
+/// the way. Returns where it ends up along with the velocity left once whatever was pushing
+/// into the obstacles is taken out.
+pub fn slide(
+    start: Vec3,
+    movement: Vec3,
+    velocity: Vec3,
+    radius: f32,
+    obstacles: &[Obstacle],
+) -> (Vec3, Vec3) {
+    let mut position = Vec3::new(start.x, 0.0, start.z);
+    let mut movement = Vec3::new(movement.x, 0.0, movement.z);
+    let mut velocity = velocity;
+    for _ in 0..MAX_SLIDES {
+        if movement.length_squared() <= f32::EPSILON {
+            break;
+        }
+
+        let hit = obstacles
+            .iter()
+            .filter_map(|obstacle| obstacle.sweep(position, movement, radius))
+            .min_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+
+        let hit = match hit {
+            Some(hit) => hit,
+            None => {
+                position += movement;
+                break;
+            }
+        };
+
+        // stop just short of whatever got hit then slide along it with what's left
+        position += (movement * hit.time) + (hit.normal * SKIN);
+        let remaining = movement * (1.0 - hit.time);
+        movement = remaining - (hit.normal * remaining.dot(hit.normal));
+
+        let into_hit = velocity.dot(hit.normal);
+        if into_hit < 0.0 {
+            velocity -= hit.normal * into_hit;
+        }
+    }
+
+    // anything left over after sliding around is dropped rather than risk going through
+    // a corner
+    (Vec3::new(position.x, start.y, position.z), velocity)
+}
+
+/// Like slide, but whatever gets hit knocks the velocity back off of it instead of letting it
+/// slide along, losing speed by the restitution. Only the x/z part of the move can be stopped,
+/// the height keeps going.
+pub fn deflect(
+    start: Vec3,
+    movement: Vec3,
+    velocity: Vec3,
+    radius: f32,
+    restitution: f32,
+    obstacles: &[Obstacle],
+) -> (Vec3, Vec3) {
+    let flat_start = Vec3::new(start.x, 0.0, start.z);
+    let flat_movement = Vec3::new(movement.x, 0.0, movement.z);
+    let hit = obstacles
+        .iter()
+        .filter_map(|obstacle| obstacle.sweep(flat_start, flat_movement, radius))
+        .min_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+
+    let hit = match hit {
+        Some(hit) => hit,
+        None => return (start + movement, velocity),
+    };
+
+    // the rest of the move is dropped, next frame picks up with the new velocity
+    let position = flat_start + (flat_movement * hit.time) + (hit.normal * SKIN);
+    let mut velocity = velocity;
+    let into_hit = velocity.dot(hit.normal);
+    if into_hit < 0.0 {
+        velocity -= hit.normal * into_hit * (1.0 + restitution);
+    }
+
+    (
+        Vec3::new(position.x, start.y + movement.y, position.z),
+        velocity,
+    )
+}
+
+/// The velocity after bouncing off the inside of the bounds, if new is past one of its edges
+/// and still heading further out. Anything already heading back in is left alone so things
+/// coming in from outside the field can make their way in.
+pub fn reflect_off_bounds(
+    new: Vec3,
+    velocity: Vec3,
+    restitution: f32,
+    bounds: &WorldAabb,
+) -> Option<Vec3> {
+    let mut reflected = velocity;
+    if (new.z <= bounds.min.z && velocity.z < 0.0) || (new.z >= bounds.max.z && velocity.z > 0.0) {
+        reflected.z *= -restitution;
+    }
+    if (new.x <= bounds.min.x && velocity.x < 0.0) || (new.x >= bounds.max.x && velocity.x > 0.0) {
+        reflected.x *= -restitution;
+    }
+
+    if reflected == velocity {
+        None
+    } else {
+        Some(reflected)
+    }
+}
+
+/// How far to push two overlapping circles apart, split up by mass so the heavier one moves
+/// less. Circles that aren't touching don't get pushed at all.
+pub fn separation(
+    a: Vec3,
+    a_radius: f32,
+    a_mass: f32,
+    b: Vec3,
+    b_radius: f32,
+    b_mass: f32,
+) -> Option<(Vec3, Vec3)> {
+    let offset = Vec3::new(b.x - a.x, 0.0, b.z - a.z);
+    let distance = offset.length();
+    let overlap = (a_radius + b_radius) - distance;
+    if overlap <= 0.0 {
+        return None;
+    }
+
+    // right on top of each other, any direction will do
+    let normal = if distance > f32::EPSILON {
+        offset / distance
+    } else {
+        Vec3::X
+    };
+    let total_mass = a_mass.max(0.0) + b_mass.max(0.0);
+    let a_share = if total_mass > f32::EPSILON {
+        b_mass.max(0.0) / total_mass
+    } else {
+        0.5
+    };
+
+    Some((
+        -normal * overlap * a_share,
+        normal * overlap * (1.0 - a_share),
+    ))
+}
+
+/// Where a circle moving along the x/z plane first touches the box, if it does at all. A circle
+/// that already overlaps the box is only stopped if it's moving further in.
+pub fn sweep_circle_aabb(
+    start: Vec3,
+    movement: Vec3,
+    radius: f32,
+    aabb: &WorldAabb,
+) -> Option<Hit> {
+    let (min, max) = (aabb.min.min(aabb.max), aabb.min.max(aabb.max));
+    let closest = Vec3::new(
+        start.x.clamp(min.x, max.x),
+        0.0,
+        start.z.clamp(min.z, max.z),
+    );
+    let offset = Vec3::new(start.x - closest.x, 0.0, start.z - closest.z);
+    if offset.length_squared() < radius * radius {
+        let normal = if offset.length_squared() > f32::EPSILON {
+            offset.normalize()
+        } else {
+            // the center is inside the box, push out the nearest side
+            [
+                (start.x - min.x, -Vec3::X),
+                (max.x - start.x, Vec3::X),
+                (start.z - min.z, -Vec3::Z),
+                (max.z - start.z, Vec3::Z),
+            ]
+            .into_iter()
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+            .map(|(_, normal)| normal)
+            .unwrap_or(Vec3::X)
+        };
+
+        return if movement.dot(normal) < 0.0 {
+            Some(Hit { time: 0.0, normal })
+        } else {
+            None
+        };
+    }
+
+    // sweep the center against the box grown by the radius
+    let mut entry = f32::MIN;
+    let mut exit = f32::MAX;
+    let mut normal = Vec3::ZERO;
+    for (from, step, low, high, axis) in [
+        (start.x, movement.x, min.x - radius, max.x + radius, Vec3::X),
+        (start.z, movement.z, min.z - radius, max.z + radius, Vec3::Z),
+    ] {
+        if step.abs() <= f32::EPSILON {
+            if from <= low || from >= high {
+                return None;
+            }
+            continue;
+        }
+
+        let (near, far) = ((low - from) / step, (high - from) / step);
+        let (near, far, side) = if near < far {
+            (near, far, -axis)
+        } else {
+            (far, near, axis)
+        };
+        if near > entry {
+            entry = near;
+            normal = side;
+        }
+        exit = exit.min(far);
+    }
+    if entry > exit || entry > 1.0 {
+        return None;
+    }
+    if entry < 0.0 {
+        // already inside the grown box without touching the real one, so it's sitting in one
+        // of the rounded off corners
+        return sweep_circle_point(start, movement, radius, closest);
+    }
+
+    // the grown box has square corners but the real shape is rounded there
+    let point = start + (movement * entry);
+    let corner = Vec3::new(
+        point.x.clamp(min.x, max.x),
+        0.0,
+        point.z.clamp(min.z, max.z),
+    );
+    if corner.x != point.x && corner.z != point.z {
+        return sweep_circle_point(start, movement, radius, corner);
+    }
+
+    Some(Hit {
+        time: entry,
+        normal,
+    })
+}
+
+/// Where a circle moving along the x/z plane first touches a point.
+fn sweep_circle_point(start: Vec3, movement: Vec3, radius: f32, point: Vec3) -> Option<Hit> {
+    let movement = Vec3::new(movement.x, 0.0, movement.z);
+    let offset = Vec3::new(start.x - point.x, 0.0, start.z - point.z);
+    let a = movement.dot(movement);
+    let b = 2.0 * offset.dot(movement);
+    let c = offset.dot(offset) - (radius * radius);
+    let discriminant = (b * b) - (4.0 * a * c);
+    if a <= f32::EPSILON || discriminant < 0.0 {
+        return None;
+    }
+
+    let time = (-b - discriminant.sqrt()) / (2.0 * a);
+    if !(0.0..=1.0).contains(&time) {
+        return None;
+    }
+
+    Some(Hit {
+        time,
+        normal: (offset + (movement * time)).normalize_or_zero(),
+    })
+}
+
+/// Same as sweep_circle_aabb but the box is in the local space of the matrix.
+pub fn sweep_circle_oriented_aabb(
+    start: Vec3,
+    movement: Vec3,
+    radius: f32,
+    local_aabb: &WorldAabb,
+    matrix: &Mat4,
+) -> Option<Hit> {
+    let inverse_matrix = matrix.inverse();
+    let scale = matrix.transform_vector3(Vec3::X).length().max(f32::EPSILON);
+    let local_start = inverse_matrix.transform_point3(start);
+    let local_movement = inverse_matrix.transform_vector3(movement);
+
+    sweep_circle_aabb(local_start, local_movement, radius / scale, local_aabb).and_then(|hit| {
+        let normal = matrix.transform_vector3(hit.normal);
+        let normal = Vec3::new(normal.x, 0.0, normal.z).normalize_or_zero();
+        if normal == Vec3::ZERO {
+            None
+        } else {
+            Some(Hit {
+                time: hit.time,
+                normal,
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Quat;
+    use std::f32::consts::FRAC_PI_4;
+
+    const RADIUS: f32 = 0.5;
+
+    fn aabb(min_x: f32, min_z: f32, max_x: f32, max_z: f32) -> WorldAabb {
+        WorldAabb {
+            min: Vec3::new(min_x, 0.0, min_z),
+            max: Vec3::new(max_x, 0.0, max_z),
+        }
+    }
+
+    fn field() -> WorldAabb {
+        aabb(-20.0, -40.0, 20.0, 40.0)
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.01, "{} != {}", a, b);
+    }
+
+    fn assert_close_vec(a: Vec3, b: Vec3) {
+        assert!(a.distance(b) < 0.01, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn contains_inside_and_on_edges() {
+        let aabb = aabb(0.0, 0.0, 2.0, 2.0);
+        assert!(aabb.contains(Vec3::new(1.0, 0.0, 1.0)));
+        assert!(aabb.contains(Vec3::new(0.0, 0.0, 1.0)));
+        assert!(aabb.contains(Vec3::new(2.0, 0.0, 2.0)));
+        assert!(!aabb.contains(Vec3::new(2.01, 0.0, 1.0)));
+        assert!(!aabb.contains(Vec3::new(1.0, 0.0, -0.01)));
+        assert!(!aabb.contains(Vec3::new(-0.01, 0.0, -0.01)));
+    }
+
+    #[test]
+    fn contains_ignores_height() {
+        let aabb = aabb(0.0, 0.0, 2.0, 2.0);
+        assert!(aabb.contains(Vec3::new(1.0, 10.0, 1.0)));
+        assert!(aabb.contains(Vec3::new(1.0, -10.0, 1.0)));
+    }
+
+    #[test]
+    fn oriented_obstacle_contains() {
+        // a thin box rotated a quarter turn ends up lying along z
+        let obstacle = Obstacle::Oriented(
+            aabb(-2.0, -0.5, 2.0, 0.5),
+            Mat4::from_rotation_y(std::f32::consts::FRAC_PI_2),
+        );
+        assert!(obstacle.contains(Vec3::new(0.0, 0.0, 1.5)));
+        assert!(!obstacle.contains(Vec3::new(1.5, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn out_of_bounds_on_and_past_each_edge() {
+        let bounds = field();
+        assert!(!is_out_of_bounds(Vec3::ZERO, &bounds));
+        assert!(!is_out_of_bounds(Vec3::new(19.9, 0.0, 39.9), &bounds));
+        assert!(is_out_of_bounds(Vec3::new(0.0, 0.0, -40.0), &bounds));
+        assert!(is_out_of_bounds(Vec3::new(0.0, 0.0, 40.0), &bounds));
+        assert!(is_out_of_bounds(Vec3::new(-20.0, 0.0, 0.0), &bounds));
+        assert!(is_out_of_bounds(Vec3::new(20.0, 0.0, 0.0), &bounds));
+        assert!(is_out_of_bounds(Vec3::new(25.0, 0.0, 45.0), &bounds));
+    }
+
+    #[test]
+    fn no_bounce_inside_the_field() {
+        assert!(bounce_off_bounds(Vec3::ZERO, Vec3::new(5.0, 0.0, 5.0), &field()).is_none());
+    }
+
+    #[test]
+    fn bounces_back_off_each_edge() {
+        let bounds = field();
+        let velocity = Vec3::new(3.0, 0.5, -4.0);
+
+        let bounced = bounce_off_bounds(Vec3::new(0.0, 0.0, -41.0), velocity, &bounds).unwrap();
+        assert_eq!(bounced, Vec3::new(3.0, 0.5, 8.0));
+
+        let bounced = bounce_off_bounds(Vec3::new(0.0, 0.0, 41.0), velocity, &bounds).unwrap();
+        assert_eq!(bounced, Vec3::new(3.0, 0.5, -8.0));
+
+        let bounced = bounce_off_bounds(Vec3::new(-21.0, 0.0, 0.0), velocity, &bounds).unwrap();
+        assert_eq!(bounced, Vec3::new(6.0, 0.5, -4.0));
+
+        let bounced = bounce_off_bounds(Vec3::new(21.0, 0.0, 0.0), velocity, &bounds).unwrap();
+        assert_eq!(bounced, Vec3::new(-6.0, 0.5, -4.0));
+    }
+
+    #[test]
+    fn slow_bounces_still_push_away() {
+        let bounds = field();
+
+        let bounced = bounce_off_bounds(Vec3::new(20.0, 0.0, 0.0), Vec3::ZERO, &bounds).unwrap();
+        assert_eq!(bounced, Vec3::new(-2.0, 0.0, 0.0));
+
+        let bounced = bounce_off_bounds(
+            Vec3::new(0.0, 0.0, -40.0),
+            Vec3::new(0.0, 0.0, 0.2),
+            &bounds,
+        )
+        .unwrap();
+        assert_eq!(bounced, Vec3::new(0.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn bounce_is_away_even_when_already_moving_away() {
+        // the velocity's sign doesn't matter, the bounce always points back into the field
+        let bounced = bounce_off_bounds(
+            Vec3::new(0.0, 0.0, 40.5),
+            Vec3::new(0.0, 0.0, -3.0),
+            &field(),
+        )
+        .unwrap();
+        assert_eq!(bounced, Vec3::new(0.0, 0.0, -6.0));
+    }
+
+    #[test]
+    fn bounce_checks_the_ends_before_the_sidelines() {
+        let bounced = bounce_off_bounds(
+            Vec3::new(21.0, 0.0, 41.0),
+            Vec3::new(1.5, 0.0, 1.5),
+            &field(),
+        )
+        .unwrap();
+        assert_eq!(bounced, Vec3::new(1.5, 0.0, -3.0));
+    }
+
+    #[test]
+    fn sweep_hits_a_face_head_on() {
+        let hit = sweep_circle_aabb(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 4.0),
+            RADIUS,
+            &aabb(-1.0, 2.5, 1.0, 3.5),
+        )
+        .unwrap();
+        // touches when the center is a radius away from z = 2.5
+        assert_close(hit.time, 0.5);
+        assert_close_vec(hit.normal, -Vec3::Z);
+    }
+
+    #[test]
+    fn sweep_hits_each_face_with_its_normal() {
+        let wall = aabb(-1.0, -1.0, 1.0, 1.0);
+        for (start, movement, normal) in [
+            (Vec3::new(-3.0, 0.0, 0.0), Vec3::X * 4.0, -Vec3::X),
+            (Vec3::new(3.0, 0.0, 0.0), -Vec3::X * 4.0, Vec3::X),
+            (Vec3::new(0.0, 0.0, -3.0), Vec3::Z * 4.0, -Vec3::Z),
+            (Vec3::new(0.0, 0.0, 3.0), -Vec3::Z * 4.0, Vec3::Z),
+        ] {
+            let hit = sweep_circle_aabb(start, movement, RADIUS, &wall).unwrap();
+            assert_close(hit.time, 0.375);
+            assert_close_vec(hit.normal, normal);
+        }
+    }
+
+    #[test]
+    fn sweep_misses_when_too_short() {
+        let hit = sweep_circle_aabb(
+            Vec3::ZERO,
+            Vec3::new(0.0, 0.0, 1.0),
+            RADIUS,
+            &aabb(-1.0, 2.5, 1.0, 3.5),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn sweep_misses_when_passing_beside() {
+        let hit = sweep_circle_aabb(
+            Vec3::new(1.6, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 6.0),
+            RADIUS,
+            &aabb(-1.0, 2.5, 1.0, 3.5),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn sweep_grazes_an_edge_within_the_radius() {
+        let hit = sweep_circle_aabb(
+            Vec3::new(1.4, 0.0, 2.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            RADIUS,
+            &aabb(-1.0, 2.5, 1.0, 4.0),
+        )
+        .unwrap();
+        // 0.4 off to the side of the edge so the circle catches the rounded corner
+        let expected = (RADIUS * RADIUS - 0.4 * 0.4).sqrt();
+        assert_close(hit.time, (0.5 - expected) / 2.0);
+        assert!(hit.normal.x > 0.0 && hit.normal.z < 0.0);
+        assert_close(hit.normal.length(), 1.0);
+    }
+
+    #[test]
+    fn sweep_hits_a_corner_diagonally() {
+        let hit = sweep_circle_aabb(
+            Vec3::new(-2.0, 0.0, -2.0),
+            Vec3::new(2.0, 0.0, 2.0),
+            RADIUS,
+            &aabb(0.0, 0.0, 1.0, 1.0),
+        )
+        .unwrap();
+        // the center stops a radius away from the corner along the diagonal
+        let travelled = (2.0_f32 * 2.0_f32.sqrt()) - RADIUS;
+        assert_close(hit.time, travelled / (2.0 * 2.0_f32.sqrt()));
+        assert_close_vec(hit.normal, Vec3::new(-1.0, 0.0, -1.0).normalize());
+    }
+
+    #[test]
+    fn sweep_slips_past_a_rounded_corner() {
+        // this clips the square corner of the grown box but never gets within a radius of
+        // the actual corner
+        let hit = sweep_circle_aabb(
+            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, -1.0),
+            RADIUS,
+            &aabb(0.0, 0.0, 2.0, 2.0),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn sweep_from_a_corner_gap_moving_away_misses() {
+        // inside the grown box but outside the rounded corner
+        let hit = sweep_circle_aabb(
+            Vec3::new(-0.45, 0.0, -0.45),
+            Vec3::new(-1.0, 0.0, 0.0),
+            RADIUS,
+            &aabb(0.0, 0.0, 2.0, 2.0),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn sweep_from_a_corner_gap_moving_in_hits_the_corner() {
+        let hit = sweep_circle_aabb(
+            Vec3::new(-0.45, 0.0, -0.45),
+            Vec3::new(1.0, 0.0, 1.0),
+            RADIUS,
+            &aabb(0.0, 0.0, 2.0, 2.0),
+        )
+        .unwrap();
+        let distance = 0.45 * 2.0_f32.sqrt();
+        assert_close(hit.time, (distance - RADIUS) / 2.0_f32.sqrt());
+        assert_close_vec(hit.normal, Vec3::new(-1.0, 0.0, -1.0).normalize());
+    }
+
+    #[test]
+    fn sweep_overlapping_only_stops_moving_further_in() {
+        let wall = aabb(0.0, 0.0, 2.0, 2.0);
+        let start = Vec3::new(-0.2, 0.0, 1.0);
+
+        let hit = sweep_circle_aabb(start, Vec3::new(1.0, 0.0, 0.0), RADIUS, &wall).unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_close_vec(hit.normal, -Vec3::X);
+
+        assert!(sweep_circle_aabb(start, Vec3::new(-1.0, 0.0, 0.0), RADIUS, &wall).is_none());
+        assert!(sweep_circle_aabb(start, Vec3::new(0.0, 0.0, 1.0), RADIUS, &wall).is_none());
+    }
+
+    #[test]
+    fn sweep_from_inside_pushes_out_the_nearest_side() {
+        let wall = aabb(0.0, 0.0, 4.0, 2.0);
+        let hit = sweep_circle_aabb(
+            Vec3::new(3.8, 0.0, 1.0),
+            Vec3::new(-1.0, 0.0, 0.0),
+            RADIUS,
+            &wall,
+        )
+        .unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_close_vec(hit.normal, Vec3::X);
+    }
+
+    #[test]
+    fn sweep_catches_thin_walls_at_high_speed() {
+        // a whole frame at full speed moves much further than the wall is thick
+        let hit = sweep_circle_aabb(
+            Vec3::new(0.0, 0.0, -5.0),
+            Vec3::new(0.0, 0.0, 45.0),
+            RADIUS,
+            &aabb(-2.0, 0.0, 2.0, 0.1),
+        )
+        .unwrap();
+        assert_close(hit.time, 4.5 / 45.0);
+        assert_close_vec(hit.normal, -Vec3::Z);
+    }
+
+    #[test]
+    fn sweep_ignores_height() {
+        let hit = sweep_circle_aabb(
+            Vec3::new(0.0, 3.0, 0.0),
+            Vec3::new(0.0, -1.0, 4.0),
+            RADIUS,
+            &aabb(-1.0, 2.5, 1.0, 3.5),
+        )
+        .unwrap();
+        assert_close(hit.time, 0.5);
+        assert_eq!(hit.normal.y, 0.0);
+    }
+
+    #[test]
+    fn sweep_handles_flipped_boxes() {
+        let wall = WorldAabb {
+            min: Vec3::new(1.0, 0.0, 3.5),
+            max: Vec3::new(-1.0, 0.0, 2.5),
+        };
+        let hit = sweep_circle_aabb(Vec3::ZERO, Vec3::new(0.0, 0.0, 4.0), RADIUS, &wall).unwrap();
+        assert_close(hit.time, 0.5);
+    }
+
+    #[test]
+    fn oriented_sweep_uses_the_rotated_face() {
+        // a long thin box turned 45 degrees, lying along the x = -z diagonal
+        let matrix = Mat4::from_rotation_y(FRAC_PI_4);
+        let local = aabb(-5.0, -0.25, 5.0, 0.25);
+        let hit = sweep_circle_oriented_aabb(
+            Vec3::new(3.0, 0.0, 3.0),
+            Vec3::new(-6.0, 0.0, -6.0),
+            RADIUS,
+            &local,
+            &matrix,
+        )
+        .unwrap();
+
+        // starts 3 * sqrt(2) from the middle of the box and stops 0.75 short of it
+        let distance = 3.0 * 2.0_f32.sqrt();
+        assert_close(hit.time, (distance - 0.75) / (2.0 * distance));
+        assert_close_vec(hit.normal, Vec3::new(1.0, 0.0, 1.0).normalize());
+    }
+
+    #[test]
+    fn oriented_sweep_accounts_for_scale() {
+        let matrix = Mat4::from_scale_rotation_translation(
+            Vec3::splat(2.0),
+            Quat::IDENTITY,
+            Vec3::new(0.0, 0.0, 10.0),
+        );
+        // twice as big once scaled, so the near face ends up at z = 9
+        let local = aabb(-1.0, -0.5, 1.0, 0.5);
+        let hit = sweep_circle_oriented_aabb(
+            Vec3::ZERO,
+            Vec3::new(0.0, 0.0, 10.0),
+            RADIUS,
+            &local,
+            &matrix,
+        )
+        .unwrap();
+        assert_close(hit.time, 0.85);
+        assert_close_vec(hit.normal, -Vec3::Z);
+    }
+
+    #[test]
+    fn separation_leaves_apart_actors_alone() {
+        assert!(separation(Vec3::ZERO, 0.3, 1.0, Vec3::new(0.7, 0.0, 0.0), 0.3, 1.0).is_none());
+        // exactly touching isn't overlapping
+        assert!(separation(Vec3::ZERO, 0.3, 1.0, Vec3::new(0.0, 0.0, 0.6), 0.3, 1.0).is_none());
+    }
+
+    #[test]
+    fn separation_splits_evenly_for_equal_mass() {
+        let (a, b) = separation(Vec3::ZERO, 0.5, 1.0, Vec3::new(0.6, 0.0, 0.0), 0.5, 1.0).unwrap();
+        assert_close_vec(a, Vec3::new(-0.2, 0.0, 0.0));
+        assert_close_vec(b, Vec3::new(0.2, 0.0, 0.0));
+    }
+
+    #[test]
+    fn separation_moves_the_heavier_one_less() {
+        let (light, heavy) =
+            separation(Vec3::ZERO, 0.5, 1.0, Vec3::new(0.0, 0.0, 0.6), 0.5, 3.0).unwrap();
+        assert_close_vec(light, Vec3::new(0.0, 0.0, -0.3));
+        assert_close_vec(heavy, Vec3::new(0.0, 0.0, 0.1));
+    }
+
+    #[test]
+    fn separation_pushes_apart_actors_on_top_of_each_other() {
+        let (a, b) = separation(Vec3::ZERO, 0.3, 1.0, Vec3::ZERO, 0.3, 1.0).unwrap();
+        assert_close(a.distance(b), 0.6);
+        assert_eq!(a.y, 0.0);
+    }
+
+    #[test]
+    fn separation_ignores_height() {
+        let (a, b) = separation(
+            Vec3::new(0.0, 2.0, 0.0),
+            0.5,
+            1.0,
+            Vec3::new(0.6, -1.0, 0.0),
+            0.5,
+            1.0,
+        )
+        .unwrap();
+        assert_eq!(a.y, 0.0);
+        assert_eq!(b.y, 0.0);
+        assert_close(b.x - a.x, 0.4);
+    }
+
+    #[test]
+    fn separation_without_mass_splits_evenly() {
+        let (a, b) = separation(Vec3::ZERO, 0.5, 0.0, Vec3::new(0.6, 0.0, 0.0), 0.5, 0.0).unwrap();
+        assert_close_vec(a, -b);
+    }
+
+    #[test]
+    fn slide_moves_freely_without_obstacles() {
+        let (position, velocity) = slide(
+            Vec3::new(1.0, 0.0, 1.0),
+            Vec3::new(2.0, 0.0, -3.0),
+            Vec3::new(20.0, 0.0, -30.0),
+            RADIUS,
+            &[],
+        );
+        assert_close_vec(position, Vec3::new(3.0, 0.0, -2.0));
+        assert_eq!(velocity, Vec3::new(20.0, 0.0, -30.0));
+    }
+
+    #[test]
+    fn slide_keeps_height() {
+        let (position, _) = slide(
+            Vec3::new(0.0, 1.5, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::ZERO,
+            RADIUS,
+            &[],
+        );
+        assert_eq!(position.y, 1.5);
+    }
+
+    #[test]
+    fn slide_runs_along_a_wall() {
+        // heading diagonally into a wall along z
+        let wall = Obstacle::Aabb(aabb(1.0, -10.0, 2.0, 10.0));
+        let (position, velocity) = slide(
+            Vec3::ZERO,
+            Vec3::new(2.0, 0.0, 2.0),
+            Vec3::new(20.0, 0.0, 20.0),
+            RADIUS,
+            &[wall],
+        );
+
+        // stops a radius short of the wall but keeps all of its movement along it
+        assert_close(position.x, 0.5);
+        assert_close(position.z, 2.0);
+        assert_close_vec(velocity, Vec3::new(0.0, 0.0, 20.0));
+        assert!(!wall.contains(position));
+    }
+
+    #[test]
+    fn slide_keeps_velocity_moving_away() {
+        let wall = Obstacle::Aabb(aabb(1.0, -10.0, 2.0, 10.0));
+        let (_, velocity) = slide(
+            Vec3::ZERO,
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(-3.0, 0.0, 5.0),
+            RADIUS,
+            &[wall],
+        );
+        assert_eq!(velocity, Vec3::new(-3.0, 0.0, 5.0));
+    }
+
+    #[test]
+    fn slide_stops_in_a_corner() {
+        let walls = [
+            Obstacle::Aabb(aabb(1.0, -10.0, 2.0, 10.0)),
+            Obstacle::Aabb(aabb(-10.0, 1.0, 10.0, 2.0)),
+        ];
+        let (position, velocity) = slide(
+            Vec3::ZERO,
+            Vec3::new(3.0, 0.0, 3.0),
+            Vec3::new(30.0, 0.0, 30.0),
+            RADIUS,
+            &walls,
+        );
+        assert_close(position.x, 0.5);
+        assert_close(position.z, 0.5);
+        assert_close_vec(velocity, Vec3::ZERO);
+    }
+
+    #[test]
+    fn slide_never_tunnels_through_thin_corn() {
+        let corn = Obstacle::Aabb(aabb(-0.8, 1.0, 0.8, 1.05));
+        let (position, _) = slide(
+            Vec3::ZERO,
+            Vec3::new(0.0, 0.0, 45.0),
+            Vec3::new(0.0, 0.0, 45.0),
+            RADIUS,
+            &[corn],
+        );
+        assert!(position.z < 1.0 - RADIUS + 0.01);
+    }
+
+    #[test]
+    fn slide_takes_the_first_obstacle_hit() {
+        let walls = [
+            Obstacle::Aabb(aabb(-1.0, 6.0, 1.0, 7.0)),
+            Obstacle::Aabb(aabb(-1.0, 3.0, 1.0, 4.0)),
+        ];
+        let (position, _) = slide(
+            Vec3::ZERO,
+            Vec3::new(0.0, 0.0, 10.0),
+            Vec3::ZERO,
+            RADIUS,
+            &walls,
+        );
+        assert_close(position.z, 2.5);
+    }
+
+    #[test]
+    fn slide_around_a_rotated_box() {
+        let matrix = Mat4::from_rotation_y(FRAC_PI_4);
+        let obstacle = Obstacle::Oriented(aabb(-5.0, -0.25, 5.0, 0.25), matrix);
+        let (position, velocity) = slide(
+            Vec3::new(3.0, 0.0, 3.0),
+            Vec3::new(-6.0, 0.0, 0.0),
+            Vec3::new(-6.0, 0.0, 0.0),
+            RADIUS,
+            &[obstacle],
+        );
+        // pushed off along the diagonal instead of going through
+        assert!(!obstacle.contains(position));
+        assert!(position.x + position.z > 0.0);
+        let normal = Vec3::new(1.0, 0.0, 1.0).normalize();
+        assert!(velocity.dot(normal) >= -0.01);
+    }
+
+    #[test]
+    fn deflect_knocks_back_off_a_wall() {
+        let wall = Obstacle::Aabb(aabb(-1.0, 2.5, 1.0, 3.5));
+        let (position, velocity) = deflect(
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.5, 4.0),
+            Vec3::new(0.0, 2.0, 8.0),
+            RADIUS,
+            0.5,
+            &[wall],
+        );
+        assert_close(position.z, 2.0);
+        // the height isn't stopped by corn
+        assert_close(position.y, 1.5);
+        assert_close_vec(velocity, Vec3::new(0.0, 2.0, -4.0));
+    }
+
+    #[test]
+    fn deflect_keeps_speed_along_the_wall() {
+        let wall = Obstacle::Aabb(aabb(-10.0, 2.5, 10.0, 3.5));
+        let (_, velocity) = deflect(
+            Vec3::ZERO,
+            Vec3::new(2.0, 0.0, 4.0),
+            Vec3::new(3.0, 0.0, 6.0),
+            RADIUS,
+            1.0,
+            &[wall],
+        );
+        assert_close_vec(velocity, Vec3::new(3.0, 0.0, -6.0));
+    }
+
+    #[test]
+    fn deflect_without_a_hit_moves_the_whole_way() {
+        let wall = Obstacle::Aabb(aabb(5.0, 5.0, 6.0, 6.0));
+        let velocity = Vec3::new(1.0, -2.0, 1.0);
+        let (position, new_velocity) = deflect(
+            Vec3::new(0.0, 3.0, 0.0),
+            Vec3::new(1.0, -1.0, 1.0),
+            velocity,
+            RADIUS,
+            0.5,
+            &[wall],
+        );
+        assert_close_vec(position, Vec3::new(1.0, 2.0, 1.0));
+        assert_eq!(new_velocity, velocity);
+    }
+
+    #[test]
+    fn reflect_off_each_edge_of_the_bounds() {
+        let field = field();
+        assert!(reflect_off_bounds(Vec3::ZERO, Vec3::X, 0.5, &field).is_none());
+        for (new, velocity, reflected) in [
+            (
+                Vec3::new(0.0, 0.0, -41.0),
+                Vec3::new(1.0, 0.0, -4.0),
+                Vec3::new(1.0, 0.0, 2.0),
+            ),
+            (
+                Vec3::new(0.0, 0.0, 41.0),
+                Vec3::new(1.0, 0.0, 4.0),
+                Vec3::new(1.0, 0.0, -2.0),
+            ),
+            (
+                Vec3::new(-21.0, 0.0, 0.0),
+                Vec3::new(-4.0, 1.0, 1.0),
+                Vec3::new(2.0, 1.0, 1.0),
+            ),
+            (
+                Vec3::new(21.0, 0.0, 0.0),
+                Vec3::new(4.0, 1.0, 1.0),
+                Vec3::new(-2.0, 1.0, 1.0),
+            ),
+        ] {
+            let bounced = reflect_off_bounds(new, velocity, 0.5, &field).unwrap();
+            assert_close_vec(bounced, reflected);
+        }
+    }
+
+    #[test]
+    fn reflect_leaves_velocity_heading_back_in() {
+        let velocity = Vec3::new(0.0, 0.0, 3.0);
+        assert!(reflect_off_bounds(Vec3::new(0.0, 0.0, -41.0), velocity, 0.5, &field()).is_none());
+    }
+}
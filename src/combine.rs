@@ -1,11 +1,14 @@
 use crate::{
-    assets::GameAssets, audio::GameAudio, collision, component_adder::AnimationLink, cutscene,
-    enemy, football, game_state, maze, maze::CornStalk, player, AppState, ZeroSignum,
+    assets::GameAssets, audio::GameAudio, banter, collision, component_adder::AnimationLink,
+    cutscene, enemy, field, football, game_state, ingame, ingame_ui, maze, maze::CornStalk,
+    particles, player, rounds, spatial_grid, stats, AppState, ZeroSignum,
 };
+use bevy::pbr::NotShadowCaster;
 use bevy::prelude::*;
 use bevy::render::primitives::Aabb;
 use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
+use serde::Deserialize;
 use std::f32::consts::{PI, TAU};
 
 pub struct CombinePlugin;
@@ -14,6 +17,7 @@ impl Plugin for CombinePlugin {
         app.add_system_set(
             SystemSet::on_update(AppState::InGame)
                 .with_system(animate_combine)
+                .with_system(handle_breakdowns.before(harvest_corn))
                 .with_system(harvest_corn)
                 .with_system(detect_blade_collisions)
                 .with_system(handle_corn_collision),
@@ -45,6 +49,8 @@ pub struct Combine {
     pub target_rotation: Quat,
     pub target_x_coordinate: f32,
     pub friction: f32,
+    pub breakdown: Option<Breakdown>,
+    pub breakdown_cooldown: f32,
 }
 
 impl Combine {
@@ -58,10 +64,84 @@ impl Combine {
             target_rotation: Quat::from_rotation_y(TAU * 0.25),
             target_x_coordinate: 0.0,
             friction: 0.01,
+            breakdown: None,
+            breakdown_cooldown: 0.0,
         }
     }
+
+    fn is_on_row(&self) -> bool {
+        (self.heading == Heading::Left || self.heading == Heading::Right)
+            && self.current_rotation_time > 1.1
+    }
+}
+
+#[derive(Deserialize, Copy, Clone, PartialEq)]
+pub enum BreakdownKind {
+    Stall,
+    Reverse,
+    WheelRut,
+}
+
+#[derive(Copy, Clone)]
+pub struct Breakdown {
+    pub kind: BreakdownKind,
+    pub time_left: f32,
+}
+
+/// Controls how often the combine misbehaves during a round and in what ways.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct BreakdownSettings {
+    pub enabled: bool,
+    pub min_interval: f32,
+    pub max_interval: f32,
+    pub stall_time: f32,
+    pub reverse_time: f32,
+    pub wheel_rut_time: f32,
+    pub kinds: Vec<BreakdownKind>,
 }
 
+impl BreakdownSettings {
+    fn next_interval(&self) -> f32 {
+        if self.max_interval > self.min_interval {
+            thread_rng().gen_range(self.min_interval..self.max_interval)
+        } else {
+            self.min_interval
+        }
+    }
+}
+
+impl Default for BreakdownSettings {
+    fn default() -> Self {
+        BreakdownSettings {
+            enabled: true,
+            min_interval: 30.0,
+            max_interval: 45.0,
+            stall_time: 4.0,
+            reverse_time: 2.5,
+            wheel_rut_time: 20.0,
+            kinds: vec![BreakdownKind::Stall],
+        }
+    }
+}
+
+/// Torn up ground left behind by the combine that slows down the player.
+#[derive(Component)]
+pub struct WheelRut {
+    pub aabb: collision::WorldAabb,
+    pub time_left: f32,
+}
+
+impl WheelRut {
+    pub fn contains(&self, position: &Vec3) -> bool {
+        self.aabb.contains(*position)
+    }
+}
+
+pub const WHEEL_RUT_SLOWDOWN: f32 = 0.5;
+const WHEEL_RUT_WIDTH: f32 = 2.5;
+const WHEEL_RUT_LENGTH: f32 = 12.0;
+
 #[derive(Component)]
 pub struct CombineBlade;
 
@@ -70,9 +150,12 @@ fn handle_corn_collision(
     mut commands: Commands,
     mut corns: Query<(Entity, &mut CornStalk, &mut Transform), Without<Combine>>,
     combine_blades: Query<(&Transform, &CombineBlade, &Aabb, &GlobalTransform), Without<CornStalk>>,
+    spatial_grid: Res<spatial_grid::SpatialGrid>,
     mut game_assets: ResMut<GameAssets>,
     mut audio: GameAudio,
     mut sound_cooldown: Local<f32>,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    mut stat_event_writer: EventWriter<stats::StatEvent>,
     time: Res<Time>,
 ) {
     *sound_cooldown -= time.delta_seconds();
@@ -83,9 +166,14 @@ fn handle_corn_collision(
         let blade_inverse_transform_matrix = blade_global_matrix.inverse();
         let min: Vec3 = blade_aabb.min().into();
         let max: Vec3 = blade_aabb.max().into();
-
-        for (entity, mut corn, mut corn_transform) in &mut corns {
-            if corn.is_harvested {
+        let (world_min, world_max) = spatial_grid::world_bounds(&blade_global_matrix, min, max);
+
+        for entity in spatial_grid.corn_between(world_min, world_max) {
+            let (entity, mut corn, corn_transform) = match corns.get_mut(entity) {
+                Ok(corn) => corn,
+                Err(_) => continue,
+            };
+            if corn.is_harvested || !corn.kind.is_harvestable() {
                 continue;
             }
 
@@ -106,7 +194,12 @@ fn handle_corn_collision(
                         shrink_time: 2.0,
                     })
                     .remove::<collision::Collidable>();
-                    
+                spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                    particles::ParticleKind::Chaff,
+                    corn_translation + Vec3::Y,
+                ));
+                stat_event_writer.send(stats::StatEvent::CornHarvested);
+
                 if *sound_cooldown <= 0.0 {
                     audio.play_sfx(&game_assets.corn_harvest);
                     *sound_cooldown = 0.1;
@@ -190,41 +283,174 @@ fn detect_blade_collisions(
 
             if football_in_hitbox {
                 commands.entity(entity).despawn_recursive();
-                football_launch_event_writer.send(football::LaunchFootballEvent);
+                if football.kind == football::FootballKind::Regular {
+                    football_launch_event_writer.send(football::LaunchFootballEvent::new(
+                        football::FootballKind::Regular,
+                    ));
+                }
                 audio.play_sfx(&game_assets.football_pop);
             }
         }
     }
 }
 
+fn handle_breakdowns(
+    mut commands: Commands,
+    mut combines: Query<(&mut Combine, &Transform)>,
+    mut wheel_ruts: Query<(Entity, &mut WheelRut)>,
+    rounds: Res<rounds::Rounds>,
+    game_state: Res<game_state::GameState>,
+    game_assets: Res<GameAssets>,
+    mut textbox_event_writer: EventWriter<ingame_ui::SetTextBoxEvent>,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    mut smoke_cooldown: Local<f32>,
+    time: Res<Time>,
+) {
+    for (entity, mut wheel_rut) in &mut wheel_ruts {
+        wheel_rut.time_left -= time.delta_seconds();
+        if wheel_rut.time_left <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    let breakdown_settings = match rounds.get(game_state.current_round) {
+        Some(round) => &round.breakdowns,
+        None => return,
+    };
+
+    *smoke_cooldown -= time.delta_seconds();
+    *smoke_cooldown = smoke_cooldown.clamp(-3.0, 3.0);
+
+    for (mut combine, combine_transform) in &mut combines {
+        if let Some(mut breakdown) = combine.breakdown {
+            breakdown.time_left -= time.delta_seconds();
+            if breakdown.kind == BreakdownKind::Stall && *smoke_cooldown <= 0.0 {
+                spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                    particles::ParticleKind::Smoke,
+                    combine_transform.translation + (Vec3::Y * 4.0),
+                ));
+                *smoke_cooldown = 0.1;
+            }
+
+            combine.breakdown = if breakdown.time_left > 0.0 {
+                Some(breakdown)
+            } else {
+                combine.breakdown_cooldown = breakdown_settings.next_interval();
+                None
+            };
+            continue;
+        }
+
+        if !breakdown_settings.enabled || breakdown_settings.kinds.is_empty() {
+            continue;
+        }
+
+        if combine.breakdown_cooldown <= 0.0 {
+            combine.breakdown_cooldown = breakdown_settings.next_interval();
+        }
+        combine.breakdown_cooldown -= time.delta_seconds();
+        if combine.breakdown_cooldown > 0.0 || !combine.is_on_row() {
+            continue;
+        }
+
+        let kind = *breakdown_settings
+            .kinds
+            .choose(&mut thread_rng())
+            .expect("checked that there are breakdown kinds");
+
+        let texts = match kind {
+            BreakdownKind::Stall => vec![
+                banter::bill_talk("Uh oh, the combine is stalling out!", &game_assets),
+                banter::will_talk("Is that smoke?", &game_assets),
+            ],
+            BreakdownKind::Reverse => vec![
+                banter::bill_talk("Wait, why is it going backwards?", &game_assets),
+                banter::will_talk("I don't think it's supposed to do that.", &game_assets),
+            ],
+            BreakdownKind::WheelRut => vec![
+                banter::bill_talk("The combine just tore up the field!", &game_assets),
+                banter::will_talk("Watch your step out there.", &game_assets),
+            ],
+        };
+        textbox_event_writer.send(ingame_ui::SetTextBoxEvent { texts });
+
+        combine.breakdown = match kind {
+            BreakdownKind::Stall => Some(Breakdown {
+                kind,
+                time_left: breakdown_settings.stall_time,
+            }),
+            BreakdownKind::Reverse => Some(Breakdown {
+                kind,
+                time_left: breakdown_settings.reverse_time,
+            }),
+            BreakdownKind::WheelRut => {
+                let position = combine_transform.translation;
+                commands
+                    .spawn_bundle(PbrBundle {
+                        mesh: game_assets.particle_mesh.clone(),
+                        material: game_assets.wheel_rut_material.clone(),
+                        transform: {
+                            let mut t = Transform::from_xyz(position.x, 0.01, position.z);
+                            t.scale = Vec3::new(WHEEL_RUT_WIDTH, 0.02, WHEEL_RUT_LENGTH);
+                            t
+                        },
+                        ..Default::default()
+                    })
+                    .insert(WheelRut {
+                        aabb: collision::WorldAabb {
+                            min: Vec3::new(
+                                position.x - (WHEEL_RUT_WIDTH / 2.0),
+                                0.0,
+                                position.z - (WHEEL_RUT_LENGTH / 2.0),
+                            ),
+                            max: Vec3::new(
+                                position.x + (WHEEL_RUT_WIDTH / 2.0),
+                                0.0,
+                                position.z + (WHEEL_RUT_LENGTH / 2.0),
+                            ),
+                        },
+                        time_left: breakdown_settings.wheel_rut_time,
+                    })
+                    .insert(NotShadowCaster)
+                    .insert(ingame::CleanupMarker)
+                    .insert(Name::new("WheelRut"));
+
+                combine.breakdown_cooldown = breakdown_settings.next_interval();
+                None
+            }
+        };
+    }
+}
+
 fn harvest_corn(
     mut combines: Query<(&mut Combine, &mut Transform), Without<CornStalk>>,
     corns: Query<(&CornStalk, &Transform)>,
     game_state: Res<game_state::GameState>,
+    rounds: Res<rounds::Rounds>,
+    field: Res<field::FieldLayout>,
     time: Res<Time>,
     mut cutscene_state: ResMut<cutscene::CutsceneState>,
 ) {
+    let (left_turnaround, right_turnaround) = field.combine_turnaround();
     for (mut combine, mut combine_transform) in &mut combines {
         match combine.heading {
             Heading::Left | Heading::Right => {
-                if (combine_transform.translation.z < -(game_state.maze_size / 2.0)
+                if (combine_transform.translation.z < left_turnaround
                     && combine.heading == Heading::Left)
-                    || (combine_transform.translation.z > game_state.maze_size / 2.0
+                    || (combine_transform.translation.z > right_turnaround
                         && combine.heading == Heading::Right)
                 {
                     combine.current_rotation_time = 0.0;
                     let unharvested_corn = corns
                         .iter()
-                        .filter(|(c, _)| !c.is_harvested)
+                        .filter(|(c, _)| !c.is_harvested && c.kind.is_harvestable())
                         .collect::<Vec<_>>();
                     let mut rng = thread_rng();
 
                     if unharvested_corn.is_empty() {
                         println!("no more corn :(");
-                        match game_state.current_round {
-                            1 => cutscene_state.init(cutscene::Cutscene::RoundTwoOver),
-                            2 => cutscene_state.init(cutscene::Cutscene::RoundThreeOver),
-                            _ => cutscene_state.init(cutscene::Cutscene::RoundOneOver),
+                        if let Some(round) = rounds.get(game_state.current_round) {
+                            cutscene_state.init(round.outro);
                         }
                     }
 
@@ -296,10 +522,23 @@ fn harvest_corn(
 
             combine.velocity *= friction.powf(time.delta_seconds());
 
-            let direction = combine_transform.right();
+            let direction = match combine.breakdown.map(|b| b.kind) {
+                Some(BreakdownKind::Stall) => Vec3::ZERO,
+                Some(BreakdownKind::Reverse) => -combine_transform.right(),
+                _ => combine_transform.right(),
+            };
             let acceleration = Vec3::from(direction);
             combine.velocity += (acceleration.zero_signum() * speed) * time.delta_seconds();
 
+            // don't back out of the field while reversing
+            if combine.breakdown.map(|b| b.kind) == Some(BreakdownKind::Reverse)
+                && (combine_transform.translation.z < left_turnaround
+                    || combine_transform.translation.z > right_turnaround)
+            {
+                combine.breakdown = None;
+                combine.velocity = Vec3::ZERO;
+            }
+
             let new_translation =
                 combine_transform.translation + (combine.velocity * time.delta_seconds());
             combine_transform.translation = new_translation;
@@ -0,0 +1,223 @@
+use crate::{
+    assets::GameAssets, audio::GameAudio, combine, game_camera, game_state, ingame, ingame_ui,
+    menus, player, ui::text_size, AppState,
+};
+use bevy::prelude::*;
+
+pub struct CombineWarningPlugin;
+impl Plugin for CombineWarningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_exit(AppState::ResetInGame).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(update_warning_indicator)
+                    .with_system(play_proximity_cue),
+            );
+    }
+}
+
+// how close the blades need to be before we start warning the player
+const WARNING_DISTANCE: f32 = 40.0;
+// keeps the arrow from hugging the very edge of the window
+const EDGE_MARGIN: f32 = 40.0;
+
+#[derive(Component)]
+struct CombineWarningIndicator;
+
+fn setup(mut commands: Commands, game_assets: Res<GameAssets>, text_scaler: text_size::TextScaler) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::from_section(
+                "<",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: text_scaler.scale(menus::DEFAULT_FONT_SIZE),
+                    color: Color::RED,
+                },
+            ),
+            visibility: Visibility { is_visible: false },
+            ..Default::default()
+        })
+        .insert(CombineWarningIndicator)
+        .insert(ingame::CleanupMarker)
+        .insert(ingame_ui::CleanupMarker);
+}
+
+/// Returns how close the nearest combine blade is to the player, from 0.0 (out of range) to 1.0
+/// (right on top of them) along with where that blade is.
+fn nearest_blade_proximity(
+    combine_blades: &Query<&GlobalTransform, With<combine::CombineBlade>>,
+    player: &Transform,
+) -> Option<(f32, Vec3)> {
+    combine_blades
+        .iter()
+        .map(|blade| {
+            let blade_translation = blade.translation();
+            let distance = Vec3::new(blade_translation.x, 0.0, blade_translation.z)
+                .distance(Vec3::new(player.translation.x, 0.0, player.translation.z));
+            (
+                1.0 - (distance / WARNING_DISTANCE).clamp(0.0, 1.0),
+                blade_translation,
+            )
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// Returns the direction from the middle of the screen towards the given position if it's
+/// currently off-screen.
+fn off_screen_direction(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    position: Vec3,
+    window: Vec2,
+) -> Option<Vec2> {
+    let is_on_screen = camera
+        .world_to_viewport(camera_transform, position)
+        .map(|p| p.x >= 0.0 && p.x <= window.x && p.y >= 0.0 && p.y <= window.y)
+        .unwrap_or(false);
+    if is_on_screen {
+        return None;
+    }
+
+    let camera_space = camera_transform
+        .compute_matrix()
+        .inverse()
+        .transform_point3(position);
+    let direction = if camera_space.z > 0.0 {
+        // behind the camera, so everything is mirrored
+        -Vec2::new(camera_space.x, camera_space.y)
+    } else {
+        Vec2::new(camera_space.x, camera_space.y)
+    };
+
+    let direction = direction.normalize_or_zero();
+    if direction == Vec2::ZERO {
+        None
+    } else {
+        Some(direction)
+    }
+}
+
+fn update_warning_indicator(
+    game_state: Res<game_state::GameState>,
+    combine_blades: Query<&GlobalTransform, With<combine::CombineBlade>>,
+    players: Query<&Transform, With<player::Player>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<game_camera::PanOrbitCamera>>,
+    mut indicators: Query<(&mut Style, &mut Text, &mut Visibility), With<CombineWarningIndicator>>,
+    text_scaler: text_size::TextScaler,
+    time: Res<Time>,
+    mut flash_time: Local<f32>,
+) {
+    let window = Vec2::new(
+        text_scaler.window_size.width,
+        text_scaler.window_size.height,
+    );
+
+    let warning = match (players.get_single(), cameras.get_single()) {
+        (Ok(player), Ok((camera, camera_transform))) if game_state.combine_warning_on => {
+            nearest_blade_proximity(&combine_blades, player)
+                .filter(|(proximity, _)| *proximity > 0.0)
+                .and_then(|(proximity, blade_translation)| {
+                    off_screen_direction(camera, camera_transform, blade_translation, window)
+                        .map(|direction| (proximity, direction))
+                })
+        }
+        _ => None,
+    };
+
+    let (proximity, direction) = match warning {
+        Some(warning) => warning,
+        None => {
+            for (_, _, mut visibility) in indicators.iter_mut() {
+                visibility.is_visible = false;
+            }
+            return;
+        }
+    };
+
+    let half_window = (window / 2.0) - Vec2::splat(EDGE_MARGIN);
+    let to_edge = (half_window.x / direction.x.abs()).min(half_window.y / direction.y.abs());
+    let edge_position = (window / 2.0) + (direction * to_edge);
+
+    let arrow = if direction.x.abs() > direction.y.abs() {
+        if direction.x > 0.0 {
+            ">"
+        } else {
+            "<"
+        }
+    } else if direction.y > 0.0 {
+        "^"
+    } else {
+        "v"
+    };
+
+    // flash faster and grow as the blades get closer
+    *flash_time += time.delta_seconds() * (4.0 + (16.0 * proximity));
+    let font_size = text_scaler.scale(menus::DEFAULT_FONT_SIZE * (0.75 + (1.25 * proximity)));
+    let color = if flash_time.sin() > 0.0 {
+        Color::RED
+    } else {
+        Color::rgb(1.0, 1.0 - proximity, 1.0 - proximity)
+    };
+
+    for (mut style, mut text, mut visibility) in indicators.iter_mut() {
+        visibility.is_visible = true;
+        style.position = UiRect {
+            left: Val::Px(edge_position.x - (font_size / 4.0)),
+            bottom: Val::Px(edge_position.y - (font_size / 2.0)),
+            ..Default::default()
+        };
+        text.sections[0].value = arrow.to_string();
+        text.sections[0].style.font_size = font_size;
+        text.sections[0].style.color = color;
+    }
+}
+
+fn play_proximity_cue(
+    game_state: Res<game_state::GameState>,
+    combine_blades: Query<&GlobalTransform, With<combine::CombineBlade>>,
+    players: Query<(&Transform, &player::Player)>,
+    game_assets: Res<GameAssets>,
+    mut audio: GameAudio,
+    time: Res<Time>,
+    mut cooldown: Local<f32>,
+) {
+    if !game_state.combine_warning_on {
+        return;
+    }
+
+    *cooldown -= time.delta_seconds();
+    *cooldown = cooldown.clamp(-3.0, 3.0);
+    if *cooldown > 0.0 {
+        return;
+    }
+
+    if let Ok((player_transform, player)) = players.get_single() {
+        if player.is_dead || player.is_tackled {
+            return;
+        }
+
+        if let Some((proximity, _)) = nearest_blade_proximity(&combine_blades, player_transform) {
+            if proximity <= 0.0 {
+                return;
+            }
+
+            // beeps get quicker, louder and higher pitched the closer the combine is
+            audio.play_warning(
+                &game_assets.blip,
+                0.3 + (0.7 * proximity as f64),
+                0.8 + (0.8 * proximity as f64),
+            );
+            *cooldown = 1.0 - (0.85 * proximity);
+        }
+    }
+}
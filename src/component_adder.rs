@@ -77,6 +77,7 @@ fn add_components(
                         min: matrix.transform_point3(aabb.min().into()),
                         max: matrix.transform_point3(aabb.max().into()),
                     },
+                    kind: maze::CornKind::from_name(name.as_str()),
                 });
 
                 println!("found maze");
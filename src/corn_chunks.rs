@@ -0,0 +1,356 @@
+use crate::{
+    assets::GameAssets, editor, game_state, maze::CornKind, maze::CornStalk, maze::ShrinkCorn,
+    shaders, AppState,
+};
+use bevy::gltf::{Gltf, GltfMesh, GltfNode};
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use std::collections::{HashMap, HashSet};
+
+/// Draws the corn as one merged mesh per chunk of the field instead of a scene per stalk.
+/// CornStalk entities are still spawned for collisions and harvesting, a chunk gets rebuilt
+/// whenever one of its stalks changes.
+pub struct CornChunksPlugin;
+impl Plugin for CornChunksPlugin {
+    fn build(&self, app: &mut App) {
+        // removed stalks are only reported until the end of the frame, so like the spatial
+        // grid this runs after Update
+        app.insert_resource(CornChunks::default())
+            .add_system_to_stage(CoreStage::PostUpdate, track_corn_stalks)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                rebuild_corn_chunks.after(track_corn_stalks),
+            )
+            .add_system(add_harvested_corn_meshes)
+            .add_system_set(SystemSet::on_enter(AppState::LevelOver).with_system(reset_corn_chunks))
+            .add_system_set(SystemSet::on_exit(AppState::Editor).with_system(reset_corn_chunks));
+    }
+}
+
+const CHUNK_SIZE: f32 = 8.0;
+// how many stalks get drawn for each CornStalk
+const HIGH_GRAPHICS_STALKS_PER_CELL: usize = 4;
+const LOW_GRAPHICS_STALKS_PER_CELL: usize = 2;
+const SWAY_STRENGTH: f32 = 0.004;
+
+#[derive(Default)]
+pub struct CornChunks {
+    chunks: HashMap<IVec2, Entity>,
+    stalks: HashMap<Entity, IVec2>,
+    // the other way around, so a rebuild only looks at the stalks in its own chunk
+    chunk_stalks: HashMap<IVec2, HashSet<Entity>>,
+    dirty: HashSet<IVec2>,
+    stalk_mesh: Option<Handle<Mesh>>,
+    material: Option<Handle<shaders::CornMaterial>>,
+}
+
+impl CornChunks {
+    fn add_stalk(&mut self, entity: Entity, key: IVec2) {
+        if let Some(old_key) = self.stalks.insert(entity, key) {
+            self.remove_from_chunk(entity, old_key);
+        }
+        self.chunk_stalks.entry(key).or_default().insert(entity);
+        self.dirty.insert(key);
+    }
+
+    fn remove_stalk(&mut self, entity: Entity) {
+        if let Some(key) = self.stalks.remove(&entity) {
+            self.remove_from_chunk(entity, key);
+            self.dirty.insert(key);
+        }
+    }
+
+    fn remove_from_chunk(&mut self, entity: Entity, key: IVec2) {
+        if let Some(entities) = self.chunk_stalks.get_mut(&key) {
+            entities.remove(&entity);
+            if entities.is_empty() {
+                self.chunk_stalks.remove(&key);
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CornChunk;
+
+fn chunk_key(translation: Vec3) -> IVec2 {
+    IVec2::new(
+        (translation.x / CHUNK_SIZE).floor() as i32,
+        (translation.z / CHUNK_SIZE).floor() as i32,
+    )
+}
+
+// the chunk entities get despawned with the level, the mesh and material are kept around
+fn reset_corn_chunks(mut corn_chunks: ResMut<CornChunks>) {
+    corn_chunks.chunks.clear();
+    corn_chunks.stalks.clear();
+    corn_chunks.chunk_stalks.clear();
+    corn_chunks.dirty.clear();
+}
+
+fn track_corn_stalks(
+    mut corn_chunks: ResMut<CornChunks>,
+    corns: Query<(Entity, &CornStalk, &Transform), Changed<CornStalk>>,
+    removed_corns: RemovedComponents<CornStalk>,
+) {
+    for (entity, corn, transform) in &corns {
+        if corn.is_harvested {
+            // harvested corn gets its own mesh until it's gone
+            corn_chunks.remove_stalk(entity);
+        } else {
+            corn_chunks.add_stalk(entity, chunk_key(transform.translation));
+        }
+    }
+
+    for entity in removed_corns.iter() {
+        corn_chunks.remove_stalk(entity);
+    }
+}
+
+/// The corn stalk mesh from the glb with the node's transform baked in, along with the
+/// color of its material.
+fn load_stalk(
+    game_assets: &GameAssets,
+    assets_gltf: &Assets<Gltf>,
+    gltf_nodes: &Assets<GltfNode>,
+    gltf_meshes: &Assets<GltfMesh>,
+    meshes: &Assets<Mesh>,
+    materials: &Assets<StandardMaterial>,
+) -> Option<(Mesh, Mat4, Color)> {
+    let gltf = assets_gltf.get(&game_assets.corn_stalk)?;
+    let (mesh_handle, matrix) = gltf
+        .nodes
+        .iter()
+        .filter_map(|node| gltf_nodes.get(node))
+        .find_map(|node| {
+            node.mesh
+                .as_ref()
+                .map(|mesh| (mesh.clone(), node.transform.compute_matrix()))
+        })
+        .or_else(|| {
+            gltf.meshes
+                .first()
+                .map(|mesh| (mesh.clone(), Mat4::IDENTITY))
+        })?;
+    let primitive = gltf_meshes.get(&mesh_handle)?.primitives.first()?;
+    let mesh = meshes.get(&primitive.mesh)?.clone();
+    let color = primitive
+        .material
+        .as_ref()
+        .and_then(|material| materials.get(material))
+        .map(|material| material.base_color)
+        .unwrap_or(Color::rgb(0.3, 0.6, 0.2));
+
+    Some((mesh, matrix, color))
+}
+
+/// Merges a copy of the stalk for each placement. Placements are a transform for the stalk
+/// and a 0..1 value used for its sway phase and shading.
+fn build_corn_mesh(stalk: &Mesh, stalk_matrix: Mat4, placements: &[(Mat4, f32)]) -> Mesh {
+    let positions = match stalk.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions.clone(),
+        _ => vec![],
+    };
+    let normals = match stalk.attribute(Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) => normals.clone(),
+        _ => vec![[0.0, 1.0, 0.0]; positions.len()],
+    };
+    let uvs = match stalk.attribute(Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) => uvs.clone(),
+        _ => vec![[0.0, 0.0]; positions.len()],
+    };
+    let indices: Vec<u32> = match stalk.indices() {
+        Some(indices) => indices.iter().map(|i| i as u32).collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+
+    let mut merged_positions = Vec::with_capacity(positions.len() * placements.len());
+    let mut merged_normals = Vec::with_capacity(positions.len() * placements.len());
+    let mut merged_uvs = Vec::with_capacity(positions.len() * placements.len());
+    let mut merged_colors = Vec::with_capacity(positions.len() * placements.len());
+    let mut merged_indices = Vec::with_capacity(indices.len() * placements.len());
+
+    for (placement, random) in placements {
+        let matrix = *placement * stalk_matrix;
+        let offset = merged_positions.len() as u32;
+        for ((position, normal), uv) in positions.iter().zip(normals.iter()).zip(uvs.iter()) {
+            merged_positions.push(matrix.transform_point3(Vec3::from(*position)).to_array());
+            merged_normals.push(
+                matrix
+                    .transform_vector3(Vec3::from(*normal))
+                    .normalize_or_zero()
+                    .to_array(),
+            );
+            merged_uvs.push(*uv);
+            merged_colors.push([*random, (*random * 7.0).fract(), 0.0, 1.0]);
+        }
+        merged_indices.extend(indices.iter().map(|i| i + offset));
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, merged_positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, merged_normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, merged_uvs);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, merged_colors);
+    mesh.set_indices(Some(Indices::U32(merged_indices)));
+    mesh
+}
+
+/// Scatters the stalks drawn for a single CornStalk around its cell. Uses the stalk's own
+/// random value so a chunk looks the same every time it gets rebuilt.
+fn cell_placements(
+    transform: &Transform,
+    corn: &CornStalk,
+    stalks_per_cell: usize,
+) -> Vec<(Mat4, f32)> {
+    let random = corn.random;
+    let (stalks_per_cell, height) = match corn.kind {
+        CornKind::Short => (stalks_per_cell, 0.5),
+        CornKind::Sparse => (1, 1.0),
+        _ => (stalks_per_cell, 1.0),
+    };
+    (0..stalks_per_cell)
+        .map(|i| {
+            let seed = (random * 1000.0) + (i as f32 * 17.31);
+            let jitter = Vec3::new(
+                (seed * 12.9898).sin().fract() * 0.35,
+                0.0,
+                (seed * 78.233).sin().fract() * 0.35,
+            );
+            let phase = (seed * 43.758).sin().fract().abs();
+            let rotation = Quat::from_rotation_y(phase * std::f32::consts::TAU);
+            let scale = Vec3::new(1.0, (0.85 + (phase * 0.3)) * height, 1.0);
+
+            (
+                Mat4::from_scale_rotation_translation(
+                    scale,
+                    rotation,
+                    transform.translation + jitter,
+                ),
+                phase,
+            )
+        })
+        .collect()
+}
+
+fn rebuild_corn_chunks(
+    mut commands: Commands,
+    mut corn_chunks: ResMut<CornChunks>,
+    corns: Query<(&CornStalk, &Transform)>,
+    game_assets: Res<GameAssets>,
+    game_state: Res<game_state::GameState>,
+    assets_gltf: Res<Assets<Gltf>>,
+    gltf_nodes: Res<Assets<GltfNode>>,
+    gltf_meshes: Res<Assets<GltfMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut corn_materials: ResMut<Assets<shaders::CornMaterial>>,
+) {
+    if corn_chunks.dirty.is_empty() {
+        return;
+    }
+
+    // wait for the corn model to finish loading
+    let (stalk, stalk_matrix, color) = match load_stalk(
+        &game_assets,
+        &assets_gltf,
+        &gltf_nodes,
+        &gltf_meshes,
+        &meshes,
+        &materials,
+    ) {
+        Some(stalk) => stalk,
+        None => return,
+    };
+
+    let corn_chunks = &mut *corn_chunks;
+    if corn_chunks.material.is_none() {
+        corn_chunks.material = Some(corn_materials.add(shaders::CornMaterial {
+            color,
+            time: 0.0,
+            sway_strength: SWAY_STRENGTH,
+        }));
+    }
+    if corn_chunks.stalk_mesh.is_none() {
+        corn_chunks.stalk_mesh = Some(meshes.add(build_corn_mesh(
+            &stalk,
+            stalk_matrix,
+            &[(Mat4::IDENTITY, 0.5)],
+        )));
+    }
+    let material = corn_chunks.material.clone().unwrap_or_default();
+
+    let stalks_per_cell = if game_state.graphics_high {
+        HIGH_GRAPHICS_STALKS_PER_CELL
+    } else {
+        LOW_GRAPHICS_STALKS_PER_CELL
+    };
+
+    let placements = corn_chunks
+        .dirty
+        .iter()
+        .map(|key| {
+            let chunk_placements = corn_chunks
+                .chunk_stalks
+                .get(key)
+                .into_iter()
+                .flatten()
+                .filter_map(|entity| corns.get(*entity).ok())
+                // hay bales have a mesh of their own
+                .filter(|(corn, _)| !corn.is_harvested && corn.kind != CornKind::HayBale)
+                .flat_map(|(corn, transform)| cell_placements(transform, corn, stalks_per_cell))
+                .collect::<Vec<_>>();
+            (*key, chunk_placements)
+        })
+        .collect::<Vec<_>>();
+
+    for (key, chunk_placements) in placements {
+        let existing = corn_chunks.chunks.remove(&key);
+        if chunk_placements.is_empty() {
+            if let Some(entity) = existing {
+                commands.entity(entity).despawn_recursive();
+            }
+            continue;
+        }
+
+        let mesh = meshes.add(build_corn_mesh(&stalk, stalk_matrix, &chunk_placements));
+        let entity = match existing {
+            Some(entity) => {
+                commands.entity(entity).insert(mesh);
+                entity
+            }
+            None => commands
+                .spawn_bundle(MaterialMeshBundle {
+                    mesh,
+                    material: material.clone(),
+                    ..default()
+                })
+                .insert(CornChunk)
+                .insert(game_state::LevelOverCleanupMarker)
+                .insert(editor::CleanupMarker)
+                .insert(Name::new("CornChunk"))
+                .id(),
+        };
+        corn_chunks.chunks.insert(key, entity);
+    }
+
+    corn_chunks.dirty.clear();
+}
+
+/// Harvested corn drops out of its chunk so it gets a mesh of its own to shrink away with.
+fn add_harvested_corn_meshes(
+    mut commands: Commands,
+    corns: Query<Entity, Added<ShrinkCorn>>,
+    corn_chunks: Res<CornChunks>,
+) {
+    if let (Some(mesh), Some(material)) = (&corn_chunks.stalk_mesh, &corn_chunks.material) {
+        for entity in &corns {
+            commands
+                .entity(entity)
+                .insert(mesh.clone())
+                .insert(material.clone())
+                .insert(Visibility::default())
+                .insert(ComputedVisibility::default());
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
     asset_loading, assets::GameAssets, audio::GameAudio, cleanup, component_adder::AnimationLink,
-    football, game_camera, game_state, ingame, ingame_ui, menus, other_persons,
-    title_screen::MenuAction, ui::text_size, AppState, LEFT_GOAL,
+    field, football, game_camera, game_state, ingame, ingame_ui, menus, other_persons, stats,
+    title_screen::MenuAction, ui::text_size, AppState,
 };
 use bevy::prelude::*;
+use serde::Deserialize;
 use std::mem;
 //use rand::Rng;
 use leafwing_input_manager::prelude::ActionState;
@@ -67,7 +68,7 @@ impl CutsceneState {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Deserialize, Copy, Clone)]
 pub enum Cutscene {
     Intro,
     Death,
@@ -97,11 +98,13 @@ fn play_cutscene(
     mut assets_handler: asset_loading::AssetsHandler,
     mut game_assets: ResMut<GameAssets>,
     mut game_state: ResMut<game_state::GameState>,
+    field: Res<field::FieldLayout>,
     will_animation_link: Query<&AnimationLink, With<other_persons::WillPerson>>,
     bill_animation_link: Query<&AnimationLink, With<other_persons::BillPerson>>,
     mut animations: Query<&mut AnimationPlayer>,
     //mut football_launch_event_writer: EventWriter<football::LaunchFootballEvent>,
     mut ingame_ui_textbox: ResMut<ingame_ui::TextBox>,
+    run_stats: Res<stats::RunStats>,
     mut audio: GameAudio,
 ) {
     if let Ok(will_link_check) = will_animation_link.get_single() {
@@ -201,7 +204,7 @@ fn play_cutscene(
                     camera.translation = Vec3::new(
                         game_camera::INGAME_CAMERA_X,
                         game_camera::INGAME_CAMERA_Y,
-                        LEFT_GOAL,
+                        field.left_goal,
                     );
                     camera.rotation = Quat::from_axis_angle(
                         game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -392,7 +395,7 @@ fn play_cutscene(
                     camera.translation = Vec3::new(
                         game_camera::INGAME_CAMERA_X,
                         game_camera::INGAME_CAMERA_Y,
-                        LEFT_GOAL,
+                        field.left_goal,
                     );
                     camera.rotation = Quat::from_axis_angle(
                         game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -1064,7 +1067,7 @@ fn play_cutscene(
                     camera.translation = Vec3::new(
                         game_camera::INGAME_CAMERA_X,
                         game_camera::INGAME_CAMERA_Y,
-                        LEFT_GOAL,
+                        field.left_goal,
                     );
                     camera.rotation = Quat::from_axis_angle(
                         game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -1098,7 +1101,7 @@ fn play_cutscene(
                     camera.translation = Vec3::new(
                         game_camera::INGAME_CAMERA_X,
                         game_camera::INGAME_CAMERA_Y,
-                        LEFT_GOAL,
+                        field.left_goal,
                     );
                     camera.rotation = Quat::from_axis_angle(
                         game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -1138,14 +1141,16 @@ fn play_cutscene(
                     will_animation = Some(game_assets.host_talk.clone());
                 }
                 2 => {
-                    if game_state.score == 0 {
+                    // the score says little about how many touchdowns it took
+                    let touchdowns = run_stats.round.touchdowns;
+                    if touchdowns == 0 {
                         textbox.queued_text = Some(TextBoxText {
                             text: "Yeah.. no touch downs though..".to_string(),
                             speed: text_speed,
                             auto: false,
                             speaking: DisplayCharacter::Bill,
                         });
-                    } else if game_state.score == 100 {
+                    } else if touchdowns == 1 {
                         textbox.queued_text = Some(TextBoxText {
                             text: "It was only one, but it was a great touchdown.".to_string(),
                             speed: text_speed,
@@ -1211,14 +1216,14 @@ fn play_cutscene(
                     camera.translation = Vec3::new(
                         game_camera::INGAME_CAMERA_X,
                         game_camera::INGAME_CAMERA_Y,
-                        LEFT_GOAL,
+                        field.left_goal,
                     );
                     camera.rotation = Quat::from_axis_angle(
                         game_camera::INGAME_CAMERA_ROTATION_AXIS,
                         game_camera::INGAME_CAMERA_ROTATION_ANGLE,
                     );
                     cutscene_state.current = None;
-                    assets_handler.load(AppState::LevelOver, &mut game_assets, &game_state);
+                    assets_handler.load(AppState::Results, &mut game_assets, &game_state);
                 }
             },
             Cutscene::RoundTwoOver => match cutscene_state.cutscene_index {
@@ -1299,14 +1304,14 @@ fn play_cutscene(
                     camera.translation = Vec3::new(
                         game_camera::INGAME_CAMERA_X,
                         game_camera::INGAME_CAMERA_Y,
-                        LEFT_GOAL,
+                        field.left_goal,
                     );
                     camera.rotation = Quat::from_axis_angle(
                         game_camera::INGAME_CAMERA_ROTATION_AXIS,
                         game_camera::INGAME_CAMERA_ROTATION_ANGLE,
                     );
                     cutscene_state.current = None;
-                    assets_handler.load(AppState::LevelOver, &mut game_assets, &game_state);
+                    assets_handler.load(AppState::Results, &mut game_assets, &game_state);
                 }
             },
             Cutscene::RoundThreeOver => match cutscene_state.cutscene_index {
@@ -1499,8 +1504,7 @@ fn play_cutscene(
                 }
                 _ => {
                     cutscene_state.current = None;
-                    *game_state = game_state::GameState::default();
-                    assets_handler.load(AppState::TitleScreen, &mut game_assets, &game_state);
+                    assets_handler.load(AppState::Results, &mut game_assets, &game_state);
                 }
             },
             Cutscene::Death => match game_state.death_count {
@@ -1587,7 +1591,7 @@ fn play_cutscene(
                         camera.translation = Vec3::new(
                             game_camera::INGAME_CAMERA_X,
                             game_camera::INGAME_CAMERA_Y,
-                            LEFT_GOAL,
+                            field.left_goal,
                         );
                         camera.rotation = Quat::from_axis_angle(
                             game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -1671,7 +1675,7 @@ fn play_cutscene(
                         camera.translation = Vec3::new(
                             game_camera::INGAME_CAMERA_X,
                             game_camera::INGAME_CAMERA_Y,
-                            LEFT_GOAL,
+                            field.left_goal,
                         );
                         camera.rotation = Quat::from_axis_angle(
                             game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -1704,7 +1708,7 @@ fn play_cutscene(
                         camera.translation = Vec3::new(
                             game_camera::INGAME_CAMERA_X,
                             game_camera::INGAME_CAMERA_Y,
-                            LEFT_GOAL,
+                            field.left_goal,
                         );
                         camera.rotation = Quat::from_axis_angle(
                             game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -1736,7 +1740,7 @@ fn play_cutscene(
                         camera.translation = Vec3::new(
                             game_camera::INGAME_CAMERA_X,
                             game_camera::INGAME_CAMERA_Y,
-                            LEFT_GOAL,
+                            field.left_goal,
                         );
                         camera.rotation = Quat::from_axis_angle(
                             game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -1753,7 +1757,7 @@ fn play_cutscene(
         camera.translation = Vec3::new(
             game_camera::INGAME_CAMERA_X,
             game_camera::INGAME_CAMERA_Y,
-            LEFT_GOAL,
+            field.left_goal,
         );
         camera.rotation = Quat::from_axis_angle(
             game_camera::INGAME_CAMERA_ROTATION_AXIS,
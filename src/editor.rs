@@ -0,0 +1,481 @@
+use crate::{
+    asset_loading, assets::GameAssets, audio::GameAudio, cleanup, cutscene, field::FieldLayout,
+    game_state, maze::CornKind, maze_file::MazeFile, maze_validation, menus, stats, ui::text_size,
+    AppState,
+};
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+
+/// Top down view of the field for drawing mazes. Left click paints with the current brush,
+/// right click clears a cell.
+pub struct EditorPlugin;
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorState>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Editor)
+                    .with_system(cleanup::<game_state::LevelOverCleanupMarker>)
+                    .with_system(setup),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Editor)
+                    .with_system(paint_cells)
+                    .with_system(handle_editor_keys)
+                    .with_system(update_cells.after(paint_cells))
+                    .with_system(update_status_text),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Editor).with_system(cleanup::<CleanupMarker>),
+            )
+            .add_system_set(SystemSet::on_update(AppState::InGame).with_system(leave_test_play));
+    }
+}
+
+#[derive(Component)]
+pub struct CleanupMarker;
+
+const EDITOR_LINES: usize = 18;
+const EDITOR_COLUMNS: usize = 38;
+// kept out of assets/mazes so it never gets mistaken for a shipped maze
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_LOCATION: &str = "assets/editor/maze.txt";
+#[cfg(target_arch = "wasm32")]
+const SAVE_LOCATION: &str = "local storage";
+#[cfg(target_arch = "wasm32")]
+const STORAGE_KEY: &str = "football_combine_editor_maze";
+// how much of the field fits vertically on screen, along x
+const VIEW_HEIGHT: f32 = 46.0;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Brush {
+    Corn(CornKind),
+    EnemySpawn,
+    CombineStart,
+}
+
+pub struct EditorState {
+    maze: MazeFile,
+    brush: Brush,
+    status: String,
+    has_loaded: bool,
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        EditorState {
+            maze: MazeFile::new(EDITOR_LINES, EDITOR_COLUMNS),
+            brush: Brush::Corn(CornKind::Tall),
+            status: String::new(),
+            has_loaded: false,
+        }
+    }
+}
+
+impl EditorState {
+    fn cell(&self, line: usize, column: usize) -> Option<Brush> {
+        if let Some(kind) = self.maze.grid[line][column] {
+            Some(Brush::Corn(kind))
+        } else if self.maze.enemy_spawns.contains(&(line, column)) {
+            Some(Brush::EnemySpawn)
+        } else if self.maze.combine_starts.contains(&(line, column)) {
+            Some(Brush::CombineStart)
+        } else {
+            None
+        }
+    }
+
+    fn set_cell(&mut self, line: usize, column: usize, brush: Option<Brush>) {
+        self.maze.grid[line][column] = None;
+        self.maze
+            .enemy_spawns
+            .retain(|cell| *cell != (line, column));
+        self.maze
+            .combine_starts
+            .retain(|cell| *cell != (line, column));
+
+        match brush {
+            Some(Brush::Corn(kind)) => self.maze.grid[line][column] = Some(kind),
+            Some(Brush::EnemySpawn) => self.maze.enemy_spawns.push((line, column)),
+            Some(Brush::CombineStart) => self.maze.combine_starts.push((line, column)),
+            None => (),
+        }
+    }
+
+    fn save(&mut self, field: &FieldLayout) {
+        let (min, max) = field.maze_area();
+        let report = maze_validation::validate(
+            &maze_validation::obstacles_from_layout(&self.maze.to_layout(min, max)),
+            field,
+        );
+        self.status = match write_saved(&self.maze.to_text()) {
+            Ok(_) if report.is_playable() => format!("saved to {}", SAVE_LOCATION),
+            Ok(_) => format!("saved to {} but it isn't playable", SAVE_LOCATION),
+            Err(e) => format!("couldn't save: {}", e),
+        };
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_saved() -> Option<String> {
+    std::fs::read_to_string(SAVE_LOCATION).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_saved(text: &str) -> Result<(), String> {
+    let path = std::path::Path::new(SAVE_LOCATION);
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
+
+// there's no file system on the web, so the maze goes where the high scores do
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_saved() -> Option<String> {
+    local_storage()?.get_item(STORAGE_KEY).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_saved(text: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("no local storage")?
+        .set_item(STORAGE_KEY, text)
+        .map_err(|e| format!("{:?}", e))
+}
+
+#[derive(Component)]
+struct EditorCell {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Component)]
+struct StatusText;
+
+struct EditorMaterials {
+    empty: Handle<StandardMaterial>,
+    tall: Handle<StandardMaterial>,
+    short: Handle<StandardMaterial>,
+    sparse: Handle<StandardMaterial>,
+    hay_bale: Handle<StandardMaterial>,
+    enemy_spawn: Handle<StandardMaterial>,
+    combine_start: Handle<StandardMaterial>,
+}
+
+impl EditorMaterials {
+    fn for_cell(&self, cell: Option<Brush>) -> Handle<StandardMaterial> {
+        match cell {
+            None => self.empty.clone(),
+            Some(Brush::Corn(CornKind::Tall)) => self.tall.clone(),
+            Some(Brush::Corn(CornKind::Short)) => self.short.clone(),
+            Some(Brush::Corn(CornKind::Sparse)) => self.sparse.clone(),
+            Some(Brush::Corn(CornKind::HayBale)) => self.hay_bale.clone(),
+            Some(Brush::EnemySpawn) => self.enemy_spawn.clone(),
+            Some(Brush::CombineStart) => self.combine_start.clone(),
+        }
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    mut game_state: ResMut<game_state::GameState>,
+    mut editor_state: ResMut<EditorState>,
+    field: Res<FieldLayout>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut clear_color: ResMut<ClearColor>,
+    mut audio: GameAudio,
+    text_scaler: text_size::TextScaler,
+) {
+    audio.stop_bgm();
+    game_state.test_maze = None;
+    clear_color.0 = Color::hex("1a1a1a").unwrap();
+
+    // pick up where the last saved maze left off
+    if !editor_state.has_loaded {
+        editor_state.has_loaded = true;
+        if let Some(maze) = read_saved().and_then(|text| MazeFile::parse(&text).ok()) {
+            editor_state.maze = maze;
+        }
+    }
+
+    let mut unlit = |color: Color| {
+        materials.add(StandardMaterial {
+            base_color: color,
+            unlit: true,
+            ..default()
+        })
+    };
+    let editor_materials = EditorMaterials {
+        empty: unlit(Color::rgb(0.35, 0.5, 0.25)),
+        tall: unlit(Color::rgb(0.1, 0.3, 0.05)),
+        short: unlit(Color::rgb(0.3, 0.6, 0.15)),
+        sparse: unlit(Color::rgb(0.6, 0.7, 0.3)),
+        hay_bale: unlit(Color::rgb(0.85, 0.7, 0.3)),
+        enemy_spawn: unlit(Color::rgb(0.8, 0.15, 0.15)),
+        combine_start: unlit(Color::rgb(0.2, 0.35, 0.85)),
+    };
+    let field_material = unlit(Color::rgb(0.25, 0.4, 0.2));
+    let end_zone_material = unlit(Color::rgb(0.15, 0.2, 0.45));
+
+    let plane = meshes.add(Mesh::from(shape::Plane { size: 1.0 }));
+    let center = field.center();
+
+    commands
+        .spawn_bundle(Camera3dBundle {
+            projection: OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical(VIEW_HEIGHT),
+                ..default()
+            }
+            .into(),
+            // top sideline at the top of the screen, left end zone on the left
+            transform: Transform::from_translation(center + (Vec3::Y * 50.0))
+                .looking_at(center, Vec3::X),
+            ..default()
+        })
+        .insert(CleanupMarker);
+
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: plane.clone(),
+            material: field_material,
+            transform: Transform::from_xyz(
+                center.x,
+                -0.02,
+                (field.left_end + field.right_end) / 2.0,
+            )
+            .with_scale(Vec3::new(
+                field.top_end - field.bottom_end,
+                1.0,
+                field.right_end - field.left_end,
+            )),
+            ..default()
+        })
+        .insert(CleanupMarker);
+    for (start, end) in [
+        (field.left_end, field.left_goal),
+        (field.right_goal, field.right_end),
+    ] {
+        commands
+            .spawn_bundle(PbrBundle {
+                mesh: plane.clone(),
+                material: end_zone_material.clone(),
+                transform: Transform::from_xyz(center.x, -0.01, (start + end) / 2.0).with_scale(
+                    Vec3::new(field.top_end - field.bottom_end, 1.0, end - start),
+                ),
+                ..default()
+            })
+            .insert(CleanupMarker);
+    }
+
+    let (min, max) = field.maze_area();
+    let lines = editor_state.maze.grid.len();
+    let columns = editor_state.maze.grid[0].len();
+    let cell_size = (max - min) / Vec2::new(lines as f32, columns as f32);
+    for line in 0..lines {
+        for column in 0..columns {
+            commands
+                .spawn_bundle(PbrBundle {
+                    mesh: plane.clone(),
+                    material: editor_materials.for_cell(editor_state.cell(line, column)),
+                    transform: Transform::from_translation(
+                        editor_state.maze.cell_center(line, column, min, max),
+                    )
+                    .with_scale(Vec3::new(
+                        cell_size.x * 0.92,
+                        1.0,
+                        cell_size.y * 0.92,
+                    )),
+                    ..default()
+                })
+                .insert(EditorCell { line, column })
+                .insert(CleanupMarker);
+        }
+    }
+    commands.insert_resource(editor_materials);
+
+    let text_style = TextStyle {
+        font: game_assets.font.clone(),
+        font_size: text_scaler.scale(menus::BUTTON_LABEL_FONT_SIZE * 0.6),
+        color: Color::WHITE,
+    };
+    commands
+        .spawn_bundle(
+            TextBundle::from_sections([
+                TextSection::new(
+                    "1-4 corn, 5 enemy spawn, 6 combine start\n\
+                     left click paint, right click clear\n\
+                     S save, N clear all, P test play, Esc quit\n",
+                    text_style.clone(),
+                ),
+                TextSection::new("", text_style),
+            ])
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(5.0),
+                    left: Val::Px(15.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(StatusText)
+        .insert(CleanupMarker);
+}
+
+/// The cell under the cursor, if there is one.
+fn hovered_cell(windows: &Windows, maze: &MazeFile, field: &FieldLayout) -> Option<(usize, usize)> {
+    let window = windows.get_primary()?;
+    let cursor = window.cursor_position()?;
+    let center = field.center();
+    let aspect = window.width() / window.height();
+    // the cursor starts from the bottom left of the window
+    let x = center.x + ((cursor.y / window.height()) - 0.5) * VIEW_HEIGHT;
+    let z = center.z + ((cursor.x / window.width()) - 0.5) * VIEW_HEIGHT * aspect;
+
+    let (min, max) = field.maze_area();
+    let lines = maze.grid.len();
+    let columns = maze.grid[0].len();
+    let cell_size = (max - min) / Vec2::new(lines as f32, columns as f32);
+    let line = ((max.x - x) / cell_size.x).floor();
+    let column = ((z - min.y) / cell_size.y).floor();
+    if line < 0.0 || column < 0.0 || line as usize >= lines || column as usize >= columns {
+        return None;
+    }
+
+    Some((line as usize, column as usize))
+}
+
+fn paint_cells(
+    mut editor_state: ResMut<EditorState>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    field: Res<FieldLayout>,
+) {
+    let brush = if mouse_buttons.pressed(MouseButton::Left) {
+        Some(editor_state.brush)
+    } else if mouse_buttons.pressed(MouseButton::Right) {
+        None
+    } else {
+        return;
+    };
+
+    if let Some((line, column)) = hovered_cell(&windows, &editor_state.maze, &field) {
+        // only touch the state when something changes so the cells aren't redrawn every frame
+        if editor_state.cell(line, column) != brush {
+            editor_state.set_cell(line, column, brush);
+        }
+    }
+}
+
+fn update_cells(
+    editor_state: Res<EditorState>,
+    editor_materials: Option<Res<EditorMaterials>>,
+    mut cells: Query<(&EditorCell, &mut Handle<StandardMaterial>)>,
+) {
+    let editor_materials = match editor_materials {
+        Some(editor_materials) => editor_materials,
+        None => return,
+    };
+    if !editor_state.is_changed() {
+        return;
+    }
+
+    for (cell, mut material) in &mut cells {
+        let new_material = editor_materials.for_cell(editor_state.cell(cell.line, cell.column));
+        if *material != new_material {
+            *material = new_material;
+        }
+    }
+}
+
+fn update_status_text(
+    editor_state: Res<EditorState>,
+    mut texts: Query<&mut Text, With<StatusText>>,
+    added_texts: Query<(), Added<StatusText>>,
+) {
+    if !editor_state.is_changed() && added_texts.is_empty() {
+        return;
+    }
+
+    let brush = match editor_state.brush {
+        Brush::Corn(CornKind::Tall) => "tall corn",
+        Brush::Corn(CornKind::Short) => "short corn",
+        Brush::Corn(CornKind::Sparse) => "sparse corn",
+        Brush::Corn(CornKind::HayBale) => "hay bale",
+        Brush::EnemySpawn => "enemy spawn",
+        Brush::CombineStart => "combine start",
+    };
+    for mut text in &mut texts {
+        text.sections[1].value = format!("brush: {}\n{}", brush, editor_state.status);
+    }
+}
+
+fn handle_editor_keys(
+    keys: Res<Input<KeyCode>>,
+    mut editor_state: ResMut<EditorState>,
+    mut assets_handler: asset_loading::AssetsHandler,
+    mut game_assets: ResMut<GameAssets>,
+    mut game_state: ResMut<game_state::GameState>,
+    mut cutscene_state: ResMut<cutscene::CutsceneState>,
+    mut maze_files: ResMut<Assets<MazeFile>>,
+    mut run_stats: ResMut<stats::RunStats>,
+    field: Res<FieldLayout>,
+) {
+    let brushes = [
+        (KeyCode::Key1, Brush::Corn(CornKind::Tall)),
+        (KeyCode::Key2, Brush::Corn(CornKind::Short)),
+        (KeyCode::Key3, Brush::Corn(CornKind::Sparse)),
+        (KeyCode::Key4, Brush::Corn(CornKind::HayBale)),
+        (KeyCode::Key5, Brush::EnemySpawn),
+        (KeyCode::Key6, Brush::CombineStart),
+    ];
+    for (key, brush) in brushes {
+        if keys.just_pressed(key) {
+            editor_state.brush = brush;
+        }
+    }
+
+    if keys.just_pressed(KeyCode::S) {
+        editor_state.save(&field);
+    }
+
+    if keys.just_pressed(KeyCode::N) {
+        editor_state.maze = MazeFile::new(EDITOR_LINES, EDITOR_COLUMNS);
+        editor_state.status = "cleared".to_string();
+    }
+
+    if keys.just_pressed(KeyCode::P) {
+        let test_maze = maze_files.add(editor_state.maze.clone());
+        game_assets.maze_file = test_maze.clone();
+        game_state.test_maze = Some(test_maze);
+        game_state.corn_spawned = false;
+        cutscene_state.current = None;
+        *run_stats = stats::RunStats::default();
+        assets_handler.load(AppState::ResetInGame, &mut game_assets, &game_state);
+    }
+
+    if keys.just_pressed(KeyCode::Escape) {
+        assets_handler.load(AppState::TitleScreen, &mut game_assets, &game_state);
+    }
+}
+
+/// Escape during a test play goes back to editing the maze.
+fn leave_test_play(
+    keys: Res<Input<KeyCode>>,
+    mut assets_handler: asset_loading::AssetsHandler,
+    mut game_assets: ResMut<GameAssets>,
+    mut game_state: ResMut<game_state::GameState>,
+) {
+    if game_state.test_maze.is_some() && keys.just_pressed(KeyCode::Escape) {
+        game_state.corn_spawned = false;
+        assets_handler.load(AppState::Editor, &mut game_assets, &game_state);
+    }
+}
@@ -1,12 +1,14 @@
 use crate::{
-    assets::GameAssets, audio::GameAudio, collision, component_adder::AnimationLink, direction,
-    game_controller, game_state, ingame, maze, player, AppState, ZeroSignum, BOTTOM_END, LEFT_GOAL,
-    RIGHT_GOAL, TOP_END,
+    assets::GameAssets, audio::GameAudio, ball_carrier, collision, component_adder::AnimationLink,
+    direction, field, football, game_controller, game_state, ingame, landing_marker, maze,
+    particles, player, rounds, spatial_grid, stats, trample, AppState, ZeroSignum,
 };
 use bevy::gltf::Gltf;
 use bevy::prelude::*;
 use bevy::render::primitives::Aabb;
+use rand::prelude::SliceRandom;
 use rand::Rng;
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::f32::consts::{FRAC_PI_2, TAU};
 
@@ -28,6 +30,28 @@ impl Plugin for EnemyPlugin {
 
 pub struct SpawnEnemiesEvent;
 
+#[derive(Deserialize, Copy, Clone)]
+pub enum EnemyKind {
+    Linebacker,
+    Sprinter,
+}
+
+impl EnemyKind {
+    /// Linebackers are big enough to plug up a corridor.
+    pub fn body(self) -> collision::ActorBody {
+        match self {
+            EnemyKind::Linebacker => collision::ActorBody {
+                radius: 0.4,
+                mass: 3.0,
+            },
+            EnemyKind::Sprinter => collision::ActorBody {
+                radius: collision::ACTOR_RADIUS,
+                mass: 1.0,
+            },
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Enemy {
     pub line_of_sight: Entity,
@@ -45,18 +69,23 @@ pub struct Enemy {
     pub landing_target: Vec3,
     pub launch_starting_position: Vec3,
     pub current_flying_time: f32,
+    pub carried_football: Option<football::FootballKind>,
 }
 
 impl Enemy {
-    pub fn new(line_of_sight: Entity) -> Self {
+    pub fn new(line_of_sight: Entity, kind: EnemyKind) -> Self {
         let mut rng = rand::thread_rng();
+        let (speed, rotation_speed) = match kind {
+            EnemyKind::Linebacker => (42.0, 1.0),
+            EnemyKind::Sprinter => (55.0, 1.5),
+        };
 
         Enemy {
             line_of_sight,
             can_see_player: false,
             velocity: Vec3::default(),
-            speed: 42.0,
-            rotation_speed: 1.0,
+            speed,
+            rotation_speed,
             friction: 0.01,
             patrol_time: 0.0,
             has_dived: false,
@@ -67,6 +96,7 @@ impl Enemy {
             landing_target: Vec3::default(),
             launch_starting_position: Vec3::default(),
             current_flying_time: 0.0,
+            carried_football: None,
         }
     }
 }
@@ -85,31 +115,37 @@ fn handle_spawn_enemies_event(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     collidables: collision::Collidables,
+    field: Res<field::FieldLayout>,
     assets_gltf: Res<Assets<Gltf>>,
     game_state: Res<game_state::GameState>,
+    rounds: Res<rounds::Rounds>,
+    maze_hints: Res<maze::MazeHints>,
 ) {
     for event in spawn_enemies_event_reader.iter() {
-        let enemy_count = match game_state.current_round {
-            1 => 5,
-            2 => 6,
-            _ => 3,
+        let enemies = match rounds.get(game_state.current_round) {
+            Some(round) => round
+                .enemies
+                .iter()
+                .flat_map(|e| std::iter::repeat(e.kind).take(e.count))
+                .collect::<Vec<_>>(),
+            None => vec![],
         };
 
         if let Some(gltf) = assets_gltf.get(&game_assets.enemy.clone()) {
-            for _ in 0..enemy_count {
-                let mut target = None;
+            for kind in enemies {
                 let mut rng = rand::thread_rng();
-                let z_buffer = ((RIGHT_GOAL - LEFT_GOAL).abs() * 0.25);
-                let x_buffer = ((TOP_END - BOTTOM_END).abs() * 0.02);
-                let min_z = LEFT_GOAL + z_buffer;
-                let max_z = RIGHT_GOAL - z_buffer;
-                let min_x = BOTTOM_END + x_buffer;
-                let max_x = TOP_END - x_buffer;
+                // use the maze's spawn spots if it has any
+                let mut target = maze_hints
+                    .enemy_spawns
+                    .choose(&mut rng)
+                    .copied()
+                    .filter(|position| !collidables.is_in_collidable(position));
+                let spawn_area = field.spawn_area();
                 while target.is_none() {
                     let potential_position = Vec3::new(
-                        rng.gen_range(min_x..max_x),
+                        rng.gen_range(spawn_area.min.x..spawn_area.max.x),
                         0.0,
-                        rng.gen_range(min_z..max_z),
+                        rng.gen_range(spawn_area.min.z..spawn_area.max.z),
                     );
                     if !collidables.is_in_collidable(&potential_position) {
                         target = Some(potential_position);
@@ -140,7 +176,28 @@ fn handle_spawn_enemies_event(
                         transform: Transform::from_xyz(target.x, 0.0, target.z),
                         ..default()
                     })
-                    .insert(Enemy::new(line_of_sight_id))
+                    .insert(Enemy::new(line_of_sight_id, kind))
+                    .insert(kind.body())
+                    .with_children(|parent| {
+                        if let Some(football_gltf) = assets_gltf.get(&game_assets.football.clone())
+                        {
+                            parent
+                                .spawn_bundle(SceneBundle {
+                                    scene: football_gltf.scenes[0].clone(),
+                                    transform: {
+                                        let mut t = Transform::from_scale(Vec3::splat(2.5));
+                                        t.translation.y += 1.0;
+                                        t.translation.x += 0.5;
+                                        t.rotation = Quat::from_rotation_z(TAU * 0.75);
+
+                                        t
+                                    },
+                                    visibility: Visibility { is_visible: false },
+                                    ..default()
+                                })
+                                .insert(football::CarriedFootball);
+                        }
+                    })
                     .insert(AnimationLink { entity: None })
                     .insert(ingame::CleanupMarker)
                     .insert(Name::new("Enemy"));
@@ -149,7 +206,11 @@ fn handle_spawn_enemies_event(
     }
 }
 
-pub fn handle_flying_enemies(mut enemies: Query<(&mut Enemy, &mut Transform)>, time: Res<Time>) {
+pub fn handle_flying_enemies(
+    mut enemies: Query<(&mut Enemy, &mut Transform)>,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    time: Res<Time>,
+) {
     let flight_time = 2.0;
     let flight_height = 20.0;
 
@@ -187,6 +248,10 @@ pub fn handle_flying_enemies(mut enemies: Query<(&mut Enemy, &mut Transform)>, t
                 enemy.is_launched = false;
                 transform.translation.y = 0.0;
                 transform.rotation = Quat::IDENTITY;
+                spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                    particles::ParticleKind::Dust,
+                    transform.translation,
+                ));
             }
         }
     }
@@ -198,9 +263,21 @@ pub fn handle_enemy_blade_event(
     mut animations: Query<&mut AnimationPlayer>,
     game_assets: ResMut<GameAssets>,
     collidables: collision::Collidables,
+    field: Res<field::FieldLayout>,
+    mut carried_footballs: Query<(&football::CarriedFootball, &mut Visibility, &Parent)>,
+    mut drop_football_event_writer: EventWriter<football::DropFootballEvent>,
 ) {
     for event in enemy_blade_event_reader.iter() {
         if let Ok((mut enemy, transform, animation_link)) = enemies.get_mut(event.entity) {
+            // getting thrown by the combine knocks the ball loose
+            if let Some(kind) = enemy.carried_football.take() {
+                football::show_carried_football(&mut carried_footballs, event.entity, false);
+                drop_football_event_writer.send(football::DropFootballEvent {
+                    kind,
+                    position: transform.translation,
+                });
+            }
+
             if let Some(animation_entity) = animation_link.entity {
                 let mut animation = animations.get_mut(animation_entity).unwrap();
                 animation.play(game_assets.person_dive.clone_weak());
@@ -210,17 +287,12 @@ pub fn handle_enemy_blade_event(
 
             let mut target = None;
             let mut rng = rand::thread_rng();
-            let z_buffer = ((RIGHT_GOAL - LEFT_GOAL).abs() * 0.25);
-            let x_buffer = ((TOP_END - BOTTOM_END).abs() * 0.02);
-            let min_z = LEFT_GOAL + z_buffer;
-            let max_z = RIGHT_GOAL - z_buffer;
-            let min_x = BOTTOM_END + x_buffer;
-            let max_x = TOP_END - x_buffer;
+            let spawn_area = field.spawn_area();
             while target.is_none() {
                 let potential_position = Vec3::new(
-                    rng.gen_range(min_x..max_x),
+                    rng.gen_range(spawn_area.min.x..spawn_area.max.x),
                     0.0,
-                    rng.gen_range(min_z..max_z),
+                    rng.gen_range(spawn_area.min.z..spawn_area.max.z),
                 );
                 if !collidables.is_in_collidable(&potential_position) {
                     target = Some(potential_position);
@@ -240,11 +312,8 @@ fn scale_lines_of_sight(
     mut lines_of_sight: Query<(&mut Transform, &Aabb, &GlobalTransform), With<EnemyLineOfSight>>,
     corns: Query<(&maze::CornStalk, &Transform), Without<EnemyLineOfSight>>,
     player: Query<&Transform, (Without<EnemyLineOfSight>, With<player::Player>)>,
+    spatial_grid: Res<spatial_grid::SpatialGrid>,
 ) {
-    let unharvested_corn = corns
-        .iter()
-        .filter(|(c, _)| !c.is_harvested)
-        .collect::<Vec<_>>();
     let LOS_LENGTH = 15.0;
     for (mut enemy, enemy_transform) in &mut enemies {
         if let Ok((mut line_of_sight, los_aabb, los_global_transform)) =
@@ -275,8 +344,12 @@ fn scale_lines_of_sight(
                 }
             };
 
-            let mut corn_in_front_of_enemy = unharvested_corn
-                .iter()
+            let (world_min, world_max) =
+                spatial_grid::world_bounds(&los_global_matrix, los_min, los_max);
+            let mut corn_in_front_of_enemy = spatial_grid
+                .corn_between(world_min, world_max)
+                .filter_map(|entity| corns.get(entity).ok())
+                .filter(|(c, _)| !c.is_harvested && c.kind.blocks_sight())
                 .filter_map(|(c, t)| {
                     let corn_translation = t.translation;
                     let corn_inverse =
@@ -323,8 +396,44 @@ fn scale_lines_of_sight(
     }
 }
 
-fn move_enemy(
-    mut enemies: Query<(&mut Enemy, &mut Transform, &AnimationLink)>,
+const TRAIL_FOLLOW_SPEED: f32 = 0.6;
+const CONTEST_SPEED: f32 = 0.8;
+// how far away enemies notice where a kick is going to land
+const CONTEST_DISTANCE: f32 = 15.0;
+
+/// The closest football lying on the ground that's close enough to go after.
+fn nearest_loose_football(
+    footballs: &Query<(&football::Football, &Transform), Without<Enemy>>,
+    position: Vec3,
+) -> Option<Vec3> {
+    footballs
+        .iter()
+        .filter(|(football, _)| football.has_landed)
+        .map(|(_, transform)| Vec3::new(transform.translation.x, 0.0, transform.translation.z))
+        .filter(|target| target.distance(position) <= CONTEST_DISTANCE)
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)))
+}
+
+/// The closest spot a football is about to come down that's close enough to go after.
+fn nearest_landing(
+    landing_markers: &Query<&landing_marker::LandingMarker>,
+    position: Vec3,
+) -> Option<Vec3> {
+    landing_markers
+        .iter()
+        .map(|marker| marker.target)
+        // once there they just wait for it
+        .filter(|target| (0.5..=CONTEST_DISTANCE).contains(&target.distance(position)))
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)))
+}
+
+pub fn move_enemy(
+    mut enemies: Query<(
+        &mut Enemy,
+        &mut Transform,
+        &AnimationLink,
+        &collision::ActorBody,
+    )>,
     mut animations: Query<&mut AnimationPlayer>,
     player: Query<&Transform, (With<player::Player>, Without<Enemy>)>,
     collidables: collision::Collidables,
@@ -332,8 +441,14 @@ fn move_enemy(
     time: Res<Time>,
     game_assets: ResMut<GameAssets>,
     mut audio: GameAudio,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    trail: Res<trample::Trail>,
+    landing_markers: Query<&landing_marker::LandingMarker>,
+    footballs: Query<(&football::Football, &Transform), Without<Enemy>>,
+    carrier_route: Res<ball_carrier::CarrierRoute>,
+    mut stat_event_writer: EventWriter<stats::StatEvent>,
 ) {
-    for (mut enemy, mut enemy_transform, animation_link) in &mut enemies {
+    for (mut enemy, mut enemy_transform, animation_link, body) in &mut enemies {
         if enemy.is_launched {
             continue;
         }
@@ -350,6 +465,7 @@ fn move_enemy(
             audio.play_sfx(&game_assets.attach);
             enemy.has_dived = false;
             game_state.attached_enemies += 1;
+            stat_event_writer.send(stats::StatEvent::Attached);
         }
 
         if enemy.is_attached {
@@ -360,7 +476,11 @@ fn move_enemy(
             continue;
         }
 
-        if enemy.can_see_player && !enemy.has_dived {
+        // carriers just run for the end zone
+        let is_carrying = enemy.carried_football.is_some();
+        let is_chasing = enemy.can_see_player && !is_carrying;
+
+        if is_chasing && !enemy.has_dived {
             let direction = player.translation - enemy_transform.translation;
             let acceleration = Vec3::from(direction);
 
@@ -370,6 +490,10 @@ fn move_enemy(
             if player.translation.distance(enemy_transform.translation) < 3.0 {
                 enemy.has_dived = true;
                 audio.play_sfx(&game_assets.dive);
+                spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                    particles::ParticleKind::Dust,
+                    enemy_transform.translation,
+                ));
                 enemy.velocity =
                     (player.translation - enemy_transform.translation).normalize() * 0.5 * speed;
                 if let Some(animation_entity) = animation_link.entity {
@@ -381,28 +505,64 @@ fn move_enemy(
             }
         }
 
+        // enemies go for a kick coming down or a ball lying around nearby, otherwise corn the
+        // player flattened can be followed. all of it is a little slower than a chase
+        let goal = if is_carrying {
+            carrier_route
+                .next_waypoint(enemy_transform.translation)
+                .map(|waypoint| (waypoint, ball_carrier::CARRY_SPEED))
+        } else if enemy.can_see_player || enemy.has_dived {
+            None
+        } else {
+            nearest_landing(&landing_markers, enemy_transform.translation)
+                .or_else(|| nearest_loose_football(&footballs, enemy_transform.translation))
+                .map(|target| (target, CONTEST_SPEED))
+                .or_else(|| {
+                    trail
+                        .scent_near(enemy_transform.translation)
+                        .map(|scent| (scent, TRAIL_FOLLOW_SPEED))
+                })
+        };
+        if let Some((goal, goal_speed)) = goal {
+            let acceleration = goal - enemy_transform.translation;
+            let speed = speed * goal_speed;
+
+            enemy.velocity += (acceleration.zero_signum() * speed) * time.delta_seconds();
+            enemy.velocity = enemy.velocity.clamp_length_max(speed);
+        }
+
         let mut new_translation =
             enemy_transform.translation + (enemy.velocity * time.delta_seconds());
         collidables.fit_in(
             &enemy_transform.translation,
             &mut new_translation,
             &mut enemy.velocity,
-            &time,
+            body.radius,
         );
 
         enemy_transform.translation = new_translation;
 
         if enemy.has_dived && enemy.velocity.length() >= 0.001 {
             continue;
-        } else {
+        } else if enemy.has_dived {
+            // came up empty
             enemy.has_dived = false;
+            stat_event_writer.send(stats::StatEvent::DiveDodged);
         }
 
-        if enemy.can_see_player {
+        if is_chasing {
             let angle = (-(player.translation.z - enemy_transform.translation.z))
                 .atan2(player.translation.x - enemy_transform.translation.x);
             let rotation = Quat::from_axis_angle(Vec3::Y, angle);
 
+            if !rotation.is_nan() {
+                enemy_transform.rotation = rotation;
+            }
+        } else if let Some((goal, _)) = goal {
+            let angle = (-(goal.z - enemy_transform.translation.z))
+                .atan2(goal.x - enemy_transform.translation.x);
+            let rotation = Quat::from_axis_angle(Vec3::Y, angle);
+
             if !rotation.is_nan() {
                 enemy_transform.rotation = rotation;
             }
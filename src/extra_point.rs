@@ -0,0 +1,554 @@
+use crate::{
+    assets::GameAssets, audio::GameAudio, banter, cleanup, collision, field, football, game_camera,
+    game_state, ingame, ingame_ui, menus, player, rounds, title_screen::MenuAction, ui::text_size,
+    AppState,
+};
+use bevy::gltf::Gltf;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::Deserialize;
+
+/// After a touchdown the game stops for a try at the extra point. Kicking it through the goal
+/// posts is worth a little, carrying it back in again is worth more but has to beat the clock.
+pub struct ExtraPointPlugin;
+impl Plugin for ExtraPointPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ExtraPointState::default())
+            .add_event::<ExtraPointEvent>()
+            .add_system_set(SystemSet::on_exit(AppState::ResetInGame).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(handle_extra_point_event)
+                    .with_system(update_two_point_try),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::ExtraPoint).with_system(setup_extra_point),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::ExtraPoint)
+                    .with_system(choose_attempt)
+                    .with_system(aim_kick)
+                    .with_system(follow_kick)
+                    .with_system(retry_leaving),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::ExtraPoint).with_system(cleanup::<CleanupMarker>),
+            );
+    }
+}
+
+pub const KICK_POINTS: usize = 20;
+// where the kick is taken from, measured out from the goal line
+const KICK_DISTANCE: f32 = 10.0;
+// kicks are aimed to come down this far behind the end line
+const KICK_OVERRUN: f32 = 5.0;
+// how long the result stays up before play starts again
+const RESULT_TIME: f32 = 1.5;
+const METER_WIDTH: usize = 25;
+
+/// Controls whether a round stops for extra points and how hard they are.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct ExtraPointSettings {
+    pub enabled: bool,
+    pub aim_speed: f32,
+    pub two_point_distance: f32,
+    pub two_point_time: f32,
+}
+
+impl Default for ExtraPointSettings {
+    fn default() -> Self {
+        ExtraPointSettings {
+            enabled: true,
+            aim_speed: 2.5,
+            two_point_distance: 12.0,
+            two_point_time: 8.0,
+        }
+    }
+}
+
+pub struct ExtraPointEvent;
+
+#[derive(PartialEq, Eq)]
+enum Phase {
+    Choosing,
+    Aiming,
+    Kicking,
+    Result,
+}
+
+impl Default for Phase {
+    fn default() -> Self {
+        Phase::Choosing
+    }
+}
+
+struct Kick {
+    ball: Entity,
+    start: Vec3,
+    end_line: f32,
+    target_z: f32,
+    velocity: Vec3,
+    time: f32,
+    is_good: bool,
+}
+
+#[derive(Default)]
+pub struct ExtraPointState {
+    phase: Phase,
+    go_for_two: bool,
+    settings: ExtraPointSettings,
+    // whether the touchdown was scored in the left end zone
+    at_left: bool,
+    aim_time: f32,
+    kick: Option<Kick>,
+    result_time: f32,
+    two_point_time_left: Option<f32>,
+    // waiting on another state change to finish before the try can start
+    is_pending: bool,
+    // same for getting back to the game afterwards
+    is_leaving: bool,
+}
+
+impl ExtraPointState {
+    /// Where the needle is across the meter, from -1.0 to 1.0. The posts are at -0.5 and 0.5.
+    fn aim(&self) -> f32 {
+        (self.aim_time * self.settings.aim_speed).sin()
+    }
+}
+
+#[derive(Component)]
+struct CleanupMarker;
+
+#[derive(Component)]
+struct ExtraPointText;
+
+fn setup(
+    mut commands: Commands,
+    mut extra_point_state: ResMut<ExtraPointState>,
+    game_assets: Res<GameAssets>,
+    text_scaler: text_size::TextScaler,
+) {
+    *extra_point_state = ExtraPointState::default();
+
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: text_scaler.scale(menus::DEFAULT_FONT_SIZE),
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Percent(10.0),
+                    left: Val::Percent(30.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        )
+        .insert(ExtraPointText)
+        .insert(ingame::CleanupMarker)
+        .insert(ingame_ui::CleanupMarker);
+}
+
+fn set_text(texts: &mut Query<&mut Text, With<ExtraPointText>>, value: String) {
+    for mut text in texts.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+fn handle_extra_point_event(
+    mut extra_point_event_reader: EventReader<ExtraPointEvent>,
+    mut extra_point_state: ResMut<ExtraPointState>,
+    mut app_state: ResMut<State<AppState>>,
+    game_state: Res<game_state::GameState>,
+    rounds: Res<rounds::Rounds>,
+) {
+    if extra_point_event_reader.iter().last().is_some() {
+        let settings = rounds
+            .get(game_state.current_round)
+            .map(|round| round.extra_points.clone())
+            .unwrap_or_default();
+        *extra_point_state = ExtraPointState {
+            settings,
+            at_left: game_state.touchdown_on_leftside,
+            is_pending: true,
+            ..default()
+        };
+    }
+
+    // something else already queued a state change, so try again next frame
+    if extra_point_state.is_pending && app_state.push(AppState::ExtraPoint).is_ok() {
+        extra_point_state.is_pending = false;
+    }
+}
+
+fn setup_extra_point(mut commands: Commands) {
+    commands
+        .spawn_bundle(InputManagerBundle {
+            input_map: MenuAction::default_input_map(),
+            action_state: ActionState::default(),
+        })
+        .insert(CleanupMarker);
+}
+
+/// Hands things back to the game once the try is over, the next ball goes out from the other
+/// end like after any touchdown.
+fn finish(
+    extra_point_state: &mut ExtraPointState,
+    app_state: &mut State<AppState>,
+    game_state: &mut game_state::GameState,
+    football_launch_event_writer: &mut EventWriter<football::LaunchFootballEvent>,
+) {
+    game_state.touchdown_on_leftside = !game_state.touchdown_on_leftside;
+    football_launch_event_writer.send(football::LaunchFootballEvent::new(
+        football::FootballKind::Regular,
+    ));
+    if *app_state.current() == AppState::ExtraPoint {
+        leave(extra_point_state, app_state);
+    }
+}
+
+fn leave(extra_point_state: &mut ExtraPointState, app_state: &mut State<AppState>) {
+    // something else already queued a state change, so retry_leaving tries again next frame
+    extra_point_state.is_leaving = app_state.pop().is_err();
+}
+
+fn retry_leaving(
+    mut extra_point_state: ResMut<ExtraPointState>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if extra_point_state.is_leaving {
+        leave(&mut extra_point_state, &mut app_state);
+    }
+}
+
+/// Somewhere open at the given distance out from the goal line to start a two-point try,
+/// right on the goal line if the corn is too thick.
+fn two_point_start(
+    field: &field::FieldLayout,
+    collidables: &collision::Collidables,
+    goal_line: f32,
+    distance: f32,
+) -> Vec3 {
+    let center = Vec3::new(field.goal_post_x, 0.0, goal_line + distance);
+    // work outward from the middle of the field until there's no corn in the way
+    (0..(field.top_end - field.bottom_end) as usize)
+        .flat_map(|offset| [offset as f32, -(offset as f32)])
+        .map(|offset| center + (Vec3::X * offset))
+        .find(|position| !collidables.is_in_collidable(position))
+        .unwrap_or(Vec3::new(field.goal_post_x, 0.0, goal_line))
+}
+
+fn choose_attempt(
+    mut commands: Commands,
+    mut extra_point_state: ResMut<ExtraPointState>,
+    mut app_state: ResMut<State<AppState>>,
+    action_state: Query<&ActionState<MenuAction>>,
+    mut players: Query<(Entity, &mut player::Player, &mut Transform)>,
+    mut carried_footballs: Query<(&football::CarriedFootball, &mut Visibility, &Parent)>,
+    mut texts: Query<&mut Text, With<ExtraPointText>>,
+    field: Res<field::FieldLayout>,
+    collidables: collision::Collidables,
+    game_assets: Res<GameAssets>,
+    assets_gltf: Res<Assets<Gltf>>,
+    mut audio: GameAudio,
+) {
+    if extra_point_state.phase != Phase::Choosing || extra_point_state.is_leaving {
+        return;
+    }
+
+    let marker = |selected: bool| if selected { ">" } else { " " };
+    set_text(
+        &mut texts,
+        format!(
+            "EXTRA POINT\n{}KICK +{}   {}GO FOR TWO +{}",
+            marker(!extra_point_state.go_for_two),
+            KICK_POINTS,
+            marker(extra_point_state.go_for_two),
+            football::CONVERSION_POINTS
+        ),
+    );
+
+    let action_state = match action_state.get_single() {
+        Ok(action_state) => action_state,
+        Err(_) => return,
+    };
+    if action_state.just_pressed(MenuAction::Left) || action_state.just_pressed(MenuAction::Right) {
+        audio.play_sfx(&game_assets.blip);
+        extra_point_state.go_for_two = !extra_point_state.go_for_two;
+    }
+    if !action_state.just_pressed(MenuAction::Select) {
+        return;
+    }
+    audio.play_sfx(&game_assets.blip);
+
+    // outward is the way from the middle of the field toward the end zone that was scored in
+    let (goal_line, end_line, outward) = if extra_point_state.at_left {
+        (field.left_goal, field.left_end, -1.0)
+    } else {
+        (field.right_goal, field.right_end, 1.0)
+    };
+
+    if extra_point_state.go_for_two {
+        // back out onto the field with a fresh ball and a few seconds to get it in
+        let start = two_point_start(
+            &field,
+            &collidables,
+            goal_line,
+            -outward * extra_point_state.settings.two_point_distance,
+        );
+        for (entity, mut player, mut transform) in &mut players {
+            transform.translation = start;
+            player.velocity = Vec3::ZERO;
+            player.has_football = true;
+            player.carried_football = football::FootballKind::Conversion;
+            football::show_carried_football(&mut carried_footballs, entity, true);
+        }
+        extra_point_state.two_point_time_left = Some(extra_point_state.settings.two_point_time);
+        leave(&mut extra_point_state, &mut app_state);
+        return;
+    }
+
+    let start = Vec3::new(
+        field.goal_post_x,
+        0.0,
+        goal_line - (outward * KICK_DISTANCE),
+    );
+    for (_, _, mut transform) in &mut players {
+        transform.translation = start - (Vec3::Z * outward);
+    }
+
+    if let Some(gltf) = assets_gltf.get(&game_assets.football.clone()) {
+        let ball = commands
+            .spawn_bundle(SceneBundle {
+                scene: gltf.scenes[0].clone(),
+                transform: Transform::from_translation(start).with_scale(Vec3::splat(3.0)),
+                ..default()
+            })
+            .insert(CleanupMarker)
+            .id();
+        extra_point_state.kick = Some(Kick {
+            ball,
+            start,
+            end_line,
+            target_z: end_line + (outward * KICK_OVERRUN),
+            velocity: Vec3::ZERO,
+            time: 0.0,
+            is_good: false,
+        });
+        extra_point_state.phase = Phase::Aiming;
+    }
+}
+
+fn aim_kick(
+    mut extra_point_state: ResMut<ExtraPointState>,
+    action_state: Query<&ActionState<MenuAction>>,
+    mut texts: Query<&mut Text, With<ExtraPointText>>,
+    field: Res<field::FieldLayout>,
+    game_assets: Res<GameAssets>,
+    mut audio: GameAudio,
+    time: Res<Time>,
+) {
+    if extra_point_state.phase != Phase::Aiming {
+        return;
+    }
+
+    extra_point_state.aim_time += time.delta_seconds();
+    let aim = extra_point_state.aim();
+
+    // the posts sit halfway out from the middle of the meter
+    let needle = (((aim + 1.0) / 2.0) * (METER_WIDTH - 1) as f32).round() as usize;
+    let left_post = (METER_WIDTH - 1) / 4;
+    let right_post = METER_WIDTH - 1 - left_post;
+    let meter = (0..METER_WIDTH)
+        .map(|i| {
+            if i == needle {
+                'o'
+            } else if i == left_post || i == right_post {
+                '|'
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    set_text(&mut texts, format!("EXTRA POINT\n{}", meter));
+
+    let pressed = action_state
+        .get_single()
+        .is_ok_and(|action_state| action_state.just_pressed(MenuAction::Select));
+    if !pressed {
+        return;
+    }
+
+    if let Some(kick) = extra_point_state.kick.as_mut() {
+        // aimed so it's right over the needle when it gets to the posts
+        let at_posts = (kick.end_line - kick.start.z) / (kick.target_z - kick.start.z);
+        let target = Vec3::new(
+            field.goal_post_x + (aim * field.goal_post_width / at_posts),
+            0.0,
+            kick.target_z,
+        );
+        kick.velocity = football::kick_velocity(kick.start, target);
+
+        // it's judged where it crosses the end line, between the posts and over the crossbar
+        let crossing = (kick.end_line - kick.start.z) / kick.velocity.z;
+        let at_posts = football::flight_position(kick.start, kick.velocity, crossing);
+        kick.is_good = (at_posts.x - field.goal_post_x).abs() <= field.goal_post_width / 2.0
+            && at_posts.y >= field.crossbar_height;
+    }
+
+    audio.play_sfx(&game_assets.football_launch);
+    extra_point_state.phase = Phase::Kicking;
+}
+
+fn follow_kick(
+    mut extra_point_state: ResMut<ExtraPointState>,
+    mut game_state: ResMut<game_state::GameState>,
+    mut app_state: ResMut<State<AppState>>,
+    mut balls: Query<&mut Transform, Without<game_camera::PanOrbitCamera>>,
+    mut cameras: Query<&mut Transform, With<game_camera::PanOrbitCamera>>,
+    mut texts: Query<&mut Text, With<ExtraPointText>>,
+    game_assets: Res<GameAssets>,
+    mut audio: GameAudio,
+    time: Res<Time>,
+    mut football_launch_event_writer: EventWriter<football::LaunchFootballEvent>,
+    mut textbox_event_writer: EventWriter<ingame_ui::SetTextBoxEvent>,
+) {
+    if !matches!(extra_point_state.phase, Phase::Kicking | Phase::Result) {
+        return;
+    }
+
+    let kick = match extra_point_state.kick.as_mut() {
+        Some(kick) => kick,
+        None => return,
+    };
+    kick.time = (kick.time + time.delta_seconds()).min(football::FLIGHT_TIME);
+    let position = football::flight_position(kick.start, kick.velocity, kick.time);
+    if let Ok(mut transform) = balls.get_mut(kick.ball) {
+        transform.translation = position;
+    }
+    for mut camera in &mut cameras {
+        camera.translation.z += (position.z - camera.translation.z) * 3.0 * time.delta_seconds();
+    }
+
+    let is_good = kick.is_good;
+    if extra_point_state.phase == Phase::Kicking {
+        if kick.time < football::FLIGHT_TIME {
+            return;
+        }
+
+        if is_good {
+            game_state.score += KICK_POINTS;
+            audio.play_sfx(&game_assets.touch_down);
+            set_text(&mut texts, format!("IT'S GOOD! +{}", KICK_POINTS));
+        } else {
+            audio.play_sfx(&game_assets.bounce);
+            set_text(&mut texts, "NO GOOD".to_string());
+        }
+        extra_point_state.phase = Phase::Result;
+        extra_point_state.result_time = RESULT_TIME;
+        return;
+    }
+
+    extra_point_state.result_time -= time.delta_seconds();
+    if extra_point_state.result_time > 0.0 {
+        return;
+    }
+
+    // the ball goes away with the rest of the extra point
+    extra_point_state.kick = None;
+    set_text(&mut texts, String::new());
+    textbox_event_writer.send(ingame_ui::SetTextBoxEvent {
+        texts: if is_good {
+            vec![banter::bill_talk("The kick is good!", &game_assets)]
+        } else {
+            vec![
+                banter::will_talk("Wide! The kick is no good.", &game_assets),
+                banter::bill_talk("Should've gone for two.", &game_assets),
+            ]
+        },
+    });
+    finish(
+        &mut extra_point_state,
+        &mut app_state,
+        &mut game_state,
+        &mut football_launch_event_writer,
+    );
+}
+
+fn update_two_point_try(
+    mut extra_point_state: ResMut<ExtraPointState>,
+    mut game_state: ResMut<game_state::GameState>,
+    mut app_state: ResMut<State<AppState>>,
+    mut players: Query<(Entity, &mut player::Player)>,
+    mut carried_footballs: Query<(&football::CarriedFootball, &mut Visibility, &Parent)>,
+    mut texts: Query<&mut Text, With<ExtraPointText>>,
+    mut touchdown_event_reader: EventReader<game_state::TouchdownEvent>,
+    game_assets: Res<GameAssets>,
+    time: Res<Time>,
+    mut football_launch_event_writer: EventWriter<football::LaunchFootballEvent>,
+    mut textbox_event_writer: EventWriter<ingame_ui::SetTextBoxEvent>,
+) {
+    let converted = touchdown_event_reader
+        .iter()
+        .any(|event| event.football == football::FootballKind::Conversion);
+    if converted {
+        textbox_event_writer.send(ingame_ui::SetTextBoxEvent {
+            texts: vec![
+                banter::will_talk("They punched it in!", &game_assets),
+                banter::bill_talk("The two-point try is good!", &game_assets),
+            ],
+        });
+    }
+
+    let time_left = match extra_point_state.two_point_time_left.as_mut() {
+        Some(time_left) => time_left,
+        None => return,
+    };
+
+    // scoring it is handled like any other touchdown
+    let still_trying = players.iter().any(|(_, player)| {
+        player.has_football && player.carried_football == football::FootballKind::Conversion
+    });
+    if !still_trying {
+        extra_point_state.two_point_time_left = None;
+        set_text(&mut texts, String::new());
+        return;
+    }
+
+    *time_left -= time.delta_seconds();
+    if *time_left > 0.0 {
+        set_text(
+            &mut texts,
+            format!("TWO-POINT TRY {}", time_left.ceil() as usize),
+        );
+        return;
+    }
+
+    for (entity, mut player) in &mut players {
+        player.has_football = false;
+        football::show_carried_football(&mut carried_footballs, entity, false);
+    }
+    extra_point_state.two_point_time_left = None;
+    set_text(&mut texts, String::new());
+    textbox_event_writer.send(ingame_ui::SetTextBoxEvent {
+        texts: vec![
+            banter::bill_talk("Time's up! They didn't get it in.", &game_assets),
+            banter::will_talk("That's the risk of going for two.", &game_assets),
+        ],
+    });
+    finish(
+        &mut extra_point_state,
+        &mut app_state,
+        &mut game_state,
+        &mut football_launch_event_writer,
+    );
+}
@@ -0,0 +1,120 @@
+use crate::{collision::WorldAabb, game_state, rounds};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Keeps FieldLayout matching the round being played.
+pub struct FieldPlugin;
+impl Plugin for FieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FieldLayout::default())
+            .add_system(apply_round_layout);
+    }
+}
+
+/// Where the goal lines and the edges of the field are. x runs from the bottom sideline to the
+/// top one and z from the left end zone to the right one. Rounds can set their own in
+/// rounds.ron, anything left out comes from the regular stadium.
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct FieldLayout {
+    pub left_goal: f32,
+    pub right_goal: f32,
+    pub left_end: f32,
+    pub right_end: f32,
+    pub bottom_end: f32,
+    pub top_end: f32,
+    // the goal posts stand on the end lines, centered here across the field
+    pub goal_post_x: f32,
+    pub goal_post_width: f32,
+    pub crossbar_height: f32,
+}
+
+impl Default for FieldLayout {
+    fn default() -> Self {
+        FieldLayout {
+            left_goal: -38.5,
+            right_goal: 37.5,
+            left_end: -47.5,
+            right_end: 47.0,
+            bottom_end: -19.471,
+            top_end: 20.471,
+            goal_post_x: 0.5,
+            goal_post_width: 4.6,
+            crossbar_height: 2.5,
+        }
+    }
+}
+
+// how far past the goal lines combines drive before turning around
+const COMBINE_OVERRUN: f32 = 2.0;
+
+impl FieldLayout {
+    /// The whole field from end zone to end zone, nothing is allowed outside of it.
+    pub fn bounds(&self) -> WorldAabb {
+        WorldAabb {
+            min: Vec3::new(self.bottom_end, 0.0, self.left_end),
+            max: Vec3::new(self.top_end, 0.0, self.right_end),
+        }
+    }
+
+    /// The part of the field between the goal lines that maze files and generated mazes get
+    /// stretched over, as (x, z).
+    pub fn maze_area(&self) -> (Vec2, Vec2) {
+        (
+            Vec2::new(self.bottom_end, self.left_goal),
+            Vec2::new(self.top_end, self.right_goal),
+        )
+    }
+
+    /// Where enemies and footballs get dropped at random, they keep trying spots in here until
+    /// one isn't inside a collidable.
+    pub fn spawn_area(&self) -> WorldAabb {
+        let z_buffer = (self.right_goal - self.left_goal).abs() * 0.25;
+        let x_buffer = (self.top_end - self.bottom_end).abs() * 0.02;
+        WorldAabb {
+            min: Vec3::new(self.bottom_end + x_buffer, 0.0, self.left_goal + z_buffer),
+            max: Vec3::new(self.top_end - x_buffer, 0.0, self.right_goal - z_buffer),
+        }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        Vec3::new(
+            (self.bottom_end + self.top_end) / 2.0,
+            0.0,
+            (self.left_goal + self.right_goal) / 2.0,
+        )
+    }
+
+    /// The field is a hundred yards from goal line to goal line no matter how big it is.
+    pub fn to_yards(self, distance: f32) -> f32 {
+        distance * 100.0 / (self.right_goal - self.left_goal).abs()
+    }
+
+    /// Where the player lines up at the start of a round.
+    pub fn player_start(&self) -> Vec3 {
+        Vec3::new(0.0, 0.0, self.left_goal)
+    }
+
+    /// The z values combines turn around at, a little past each goal line.
+    pub fn combine_turnaround(&self) -> (f32, f32) {
+        (
+            self.left_goal - COMBINE_OVERRUN,
+            self.right_goal + COMBINE_OVERRUN,
+        )
+    }
+}
+
+fn apply_round_layout(
+    game_state: Res<game_state::GameState>,
+    rounds: Res<rounds::Rounds>,
+    mut field: ResMut<FieldLayout>,
+) {
+    let layout = rounds
+        .get(game_state.current_round)
+        .map(|round| round.field)
+        .unwrap_or_default();
+    // only write it when it changes so change detection means something
+    if *field != layout {
+        *field = layout;
+    }
+}
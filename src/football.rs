@@ -1,6 +1,6 @@
 use crate::{
-    assets::GameAssets, audio::GameAudio, collision, enemy, game_state, ingame, player::Player,
-    AppState, BOTTOM_END, LEFT_END, LEFT_GOAL, RIGHT_END, RIGHT_GOAL, TOP_END,
+    assets::GameAssets, audio::GameAudio, collision, enemy, field, game_state, ingame, particles,
+    player::Player, stats, AppState,
 };
 use bevy::gltf::Gltf;
 use bevy::prelude::*;
@@ -10,31 +10,160 @@ use std::f32::consts::FRAC_PI_2;
 pub struct FootballPlugin;
 impl Plugin for FootballPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<LaunchFootballEvent>().add_system_set(
-            SystemSet::on_update(AppState::InGame)
-                .with_system(check_for_football_pickup)
-                .with_system(handle_launch_football_event)
-                .with_system(move_football),
-        );
+        app.add_event::<LaunchFootballEvent>()
+            .add_event::<DropFootballEvent>()
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(check_for_football_pickup)
+                    .with_system(handle_launch_football_event)
+                    .with_system(handle_drop_football_event)
+                    .with_system(move_football),
+            );
+    }
+}
+
+// how long a kick takes to come down where it was aimed
+pub const FLIGHT_TIME: f32 = 2.0;
+const GRAVITY: f32 = 40.0;
+// how much of the falling speed comes back up on each bounce
+const BOUNCE_RESTITUTION: f32 = 0.45;
+// how much of the rolling speed is kept on each bounce
+const BOUNCE_FRICTION: f32 = 0.7;
+// footballs don't bounce straight, each bounce can kick off this far to either side
+const BOUNCE_WOBBLE: f32 = FRAC_PI_2 * 0.6;
+// bounces slower than this and the ball just settles down
+const SETTLE_SPEED: f32 = 1.5;
+// corn only gets in the way of a ball lower than this
+const CORN_HEIGHT: f32 = 2.0;
+const FOOTBALL_RADIUS: f32 = 0.3;
+// how much speed the ball keeps when it hits corn
+const CORN_RESTITUTION: f32 = 0.4;
+
+// touchdowns are worth this with a regular ball
+pub const TOUCHDOWN_POINTS: usize = 100;
+pub const GOLDEN_TOUCHDOWN_POINTS: usize = 500;
+pub const CONVERSION_POINTS: usize = 40;
+
+/// Regular balls get kicked back out after every touchdown. The others come from bonus events
+/// and are gone once they're used up.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FootballKind {
+    Regular,
+    Golden,
+    MultiBall,
+    // carried in on a two-point try after a touchdown
+    Conversion,
+}
+
+impl FootballKind {
+    pub fn points(&self) -> usize {
+        match self {
+            FootballKind::Regular | FootballKind::MultiBall => TOUCHDOWN_POINTS,
+            FootballKind::Golden => GOLDEN_TOUCHDOWN_POINTS,
+            FootballKind::Conversion => CONVERSION_POINTS,
+        }
     }
 }
 
 #[derive(Component)]
 pub struct Football {
+    pub kind: FootballKind,
     pub has_landed: bool,
-    target: Vec3,
-    starting_position: Vec3,
-    current_movement_time: f32,
+    // where the kick comes down before it starts bouncing around
+    pub target: Vec3,
+    pub has_bounced: bool,
+    velocity: Vec3,
 }
+
+impl Football {
+    /// A ball kicked from start that comes down on target after FLIGHT_TIME.
+    fn kicked(kind: FootballKind, start: Vec3, target: Vec3) -> Self {
+        Football {
+            kind,
+            has_landed: false,
+            target,
+            has_bounced: false,
+            velocity: kick_velocity(start, target),
+        }
+    }
+
+    /// A ball that's just sitting on the ground.
+    fn resting(kind: FootballKind, position: Vec3) -> Self {
+        Football {
+            kind,
+            has_landed: true,
+            target: position,
+            has_bounced: true,
+            velocity: Vec3::ZERO,
+        }
+    }
+
+    /// Seconds until the kick first hits the ground from the height it's at now. There's
+    /// nothing to wait for once it's bouncing around.
+    pub fn time_to_land(&self, height: f32) -> Option<f32> {
+        if self.has_bounced || self.has_landed {
+            return None;
+        }
+
+        let rising = self.velocity.y;
+        Some((rising + ((rising * rising) + (2.0 * GRAVITY * height.max(0.0))).sqrt()) / GRAVITY)
+    }
+}
+
+/// How fast a kick from start has to leave the ground to come down on target after
+/// FLIGHT_TIME.
+pub fn kick_velocity(start: Vec3, target: Vec3) -> Vec3 {
+    let across = (target - start) / FLIGHT_TIME;
+    Vec3::new(across.x, GRAVITY * FLIGHT_TIME / 2.0, across.z)
+}
+
+/// Where a kick is after flying for time, as long as nothing gets in its way.
+pub fn flight_position(start: Vec3, velocity: Vec3, time: f32) -> Vec3 {
+    start + (velocity * time) - (Vec3::Y * (GRAVITY * time * time / 2.0))
+}
+
 #[derive(Component)]
 pub struct CarriedFootball;
-pub struct LaunchFootballEvent;
+
+/// Shows or hides the ball held by whoever is carrying it.
+pub fn show_carried_football(
+    carried_footballs: &mut Query<(&CarriedFootball, &mut Visibility, &Parent)>,
+    carrier: Entity,
+    is_visible: bool,
+) {
+    for (_, mut visibility, parent) in carried_footballs {
+        if carrier == parent.get() {
+            visibility.is_visible = is_visible;
+        }
+    }
+}
+
+pub struct LaunchFootballEvent {
+    pub kind: FootballKind,
+}
+
+impl LaunchFootballEvent {
+    pub fn new(kind: FootballKind) -> Self {
+        LaunchFootballEvent { kind }
+    }
+}
+
+/// A carried ball that got knocked loose and is now lying on the ground.
+pub struct DropFootballEvent {
+    pub kind: FootballKind,
+    pub position: Vec3,
+}
+
+// how many spots to try when hiding a golden ball, the one with the most corn around it wins
+const GOLDEN_SPOT_TRIES: usize = 30;
+const GOLDEN_GLOW: Color = Color::rgb(1.0, 0.8, 0.2);
 
 fn handle_launch_football_event(
     mut commands: Commands,
     mut launch_football_event_reader: EventReader<LaunchFootballEvent>,
     game_assets: Res<GameAssets>,
     collidables: collision::Collidables,
+    field: Res<field::FieldLayout>,
     assets_gltf: Res<Assets<Gltf>>,
     mut game_state: ResMut<game_state::GameState>,
     mut spawn_enemies_event_writer: EventWriter<enemy::SpawnEnemiesEvent>,
@@ -42,8 +171,9 @@ fn handle_launch_football_event(
 ) {
     for event in launch_football_event_reader.iter() {
         if let Some(gltf) = assets_gltf.get(&game_assets.football.clone()) {
-            let left_side = Vec3::new(6.976, 0.0, -48.0);
-            let right_side = Vec3::new(6.976, 0.0, 48.0);
+            // launched from just behind the end lines
+            let left_side = Vec3::new(6.976, 0.0, field.left_end - 0.5);
+            let right_side = Vec3::new(6.976, 0.0, field.right_end + 1.0);
 
             let position = if game_state.touchdown_on_leftside {
                 right_side
@@ -51,44 +181,40 @@ fn handle_launch_football_event(
                 left_side
             };
 
-            let mut target = None;
             let mut rng = rand::thread_rng();
-            let z_buffer = ((RIGHT_GOAL - LEFT_GOAL).abs() * 0.25);
-            let x_buffer = ((TOP_END - BOTTOM_END).abs() * 0.02);
-            let min_z = LEFT_GOAL + z_buffer;
-            let max_z = RIGHT_GOAL - z_buffer;
-            let min_x = BOTTOM_END + x_buffer;
-            let max_x = TOP_END - x_buffer;
-            while target.is_none() {
+            let spawn_area = field.spawn_area();
+            let mut open_spot = || loop {
                 let potential_position = Vec3::new(
-                    rng.gen_range(min_x..max_x),
+                    rng.gen_range(spawn_area.min.x..spawn_area.max.x),
                     0.0,
-                    rng.gen_range(min_z..max_z),
+                    rng.gen_range(spawn_area.min.z..spawn_area.max.z),
                 );
                 if !collidables.is_in_collidable(&potential_position) {
-                    target = Some(potential_position);
+                    break potential_position;
                 }
-            }
+            };
 
-            audio.play_sfx(&game_assets.football_launch);
-            commands
-                .spawn_bundle(SceneBundle {
-                    scene: gltf.scenes[0].clone(),
-                    transform: {
-                        let mut t = Transform::from_scale(Vec3::splat(3.0));
-                        t.translation = position;
-                        t
-                    },
-                    ..default()
-                })
-                .insert(Football {
-                    has_landed: false,
-                    target: target.unwrap(),
-                    starting_position: position,
-                    current_movement_time: 0.0,
-                })
-                .insert(ingame::CleanupMarker)
-                .insert(Name::new("Ball"));
+            let football = match event.kind {
+                // golden balls are already waiting somewhere deep in the corn
+                FootballKind::Golden => {
+                    let spot = (0..GOLDEN_SPOT_TRIES)
+                        .map(|_| open_spot())
+                        .max_by_key(|spot| collidables.corn_count_near(spot, 3.0))
+                        .unwrap_or(position);
+                    Football::resting(event.kind, spot)
+                }
+                _ => {
+                    audio.play_sfx(&game_assets.football_launch);
+                    Football::kicked(event.kind, position, open_spot())
+                }
+            };
+
+            let translation = if football.has_landed {
+                football.target
+            } else {
+                position
+            };
+            spawn_football(&mut commands, gltf, football, translation);
         }
         if !game_state.enemies_spawned {
             spawn_enemies_event_writer.send(enemy::SpawnEnemiesEvent);
@@ -97,70 +223,153 @@ fn handle_launch_football_event(
     }
 }
 
+fn spawn_football(commands: &mut Commands, gltf: &Gltf, football: Football, translation: Vec3) {
+    let mut ball = commands.spawn_bundle(SceneBundle {
+        scene: gltf.scenes[0].clone(),
+        transform: {
+            let mut t = Transform::from_scale(Vec3::splat(3.0));
+            t.translation = translation;
+            t
+        },
+        ..default()
+    });
+    if football.kind == FootballKind::Golden {
+        ball.with_children(|parent| {
+            parent.spawn_bundle(PointLightBundle {
+                point_light: PointLight {
+                    color: GOLDEN_GLOW,
+                    intensity: 400.0,
+                    range: 6.0,
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.5, 0.0),
+                ..default()
+            });
+        });
+    }
+    ball.insert(football)
+        .insert(ingame::CleanupMarker)
+        .insert(Name::new("Ball"));
+}
+
+fn handle_drop_football_event(
+    mut commands: Commands,
+    mut drop_football_event_reader: EventReader<DropFootballEvent>,
+    game_assets: Res<GameAssets>,
+    assets_gltf: Res<Assets<Gltf>>,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+) {
+    for event in drop_football_event_reader.iter() {
+        if let Some(gltf) = assets_gltf.get(&game_assets.football.clone()) {
+            let position = Vec3::new(event.position.x, 0.0, event.position.z);
+            spawn_football(
+                &mut commands,
+                gltf,
+                Football::resting(event.kind, position),
+                position,
+            );
+            spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                particles::ParticleKind::Dust,
+                position,
+            ));
+        }
+    }
+}
+
 const FOOTBALL_PICKUP_DISTANCE: f32 = 1.5;
+// balls bouncing lower than this can be grabbed out of the air
+const FOOTBALL_PICKUP_HEIGHT: f32 = 2.5;
+
+/// Whether someone standing at carrier is close enough to grab the ball.
+pub fn within_reach(ball: Vec3, carrier: Vec3) -> bool {
+    let offset = ball - carrier;
+    offset.y < FOOTBALL_PICKUP_HEIGHT
+        && Vec2::new(offset.x, offset.z).length() < FOOTBALL_PICKUP_DISTANCE
+}
+
 fn check_for_football_pickup(
     mut commands: Commands,
     footballs: Query<(Entity, &Football, &Transform)>,
     mut player: Query<(Entity, &mut Player, &Transform)>,
     mut carried_footballs: Query<(&CarriedFootball, &mut Visibility, &Parent)>,
+    mut stat_event_writer: EventWriter<stats::StatEvent>,
 ) {
     for (football_entity, football, football_transform) in &footballs {
         let (player_entity, mut player, player_transform) = player.single_mut();
+        // only one ball at a time during multi-ball
+        if player.has_football {
+            continue;
+        }
 
-        if football_transform
-            .translation
-            .distance(player_transform.translation)
-            < FOOTBALL_PICKUP_DISTANCE
-        {
+        if within_reach(football_transform.translation, player_transform.translation) {
             player.has_football = true;
-
-            for (_, mut visibility, parent) in &mut carried_footballs {
-                if player_entity == parent.get() {
-                    visibility.is_visible = true;
-                }
-            }
+            player.carried_football = football.kind;
+            show_carried_football(&mut carried_footballs, player_entity, true);
+            stat_event_writer.send(stats::StatEvent::Pickup);
 
             commands.entity(football_entity).despawn_recursive();
         }
     }
 }
 
-fn move_football(mut footballs: Query<(&mut Football, &mut Transform)>, time: Res<Time>) {
-    let flight_time = 2.0;
-    let flight_height = 20.0;
-
+pub fn move_football(
+    mut footballs: Query<(&mut Football, &mut Transform)>,
+    collidables: collision::Collidables,
+    game_assets: Res<GameAssets>,
+    mut audio: GameAudio,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    time: Res<Time>,
+) {
+    let mut rng = rand::thread_rng();
     for (mut football, mut transform) in &mut footballs {
-        if !football.has_landed {
-            let (target_with_height, start_with_height) =
-                if football.current_movement_time / flight_time <= 0.5 {
-                    (
-                        Vec3::new(football.target.x, flight_height, football.target.z),
-                        football.starting_position,
-                    )
-                } else {
-                    (
-                        football.target,
-                        (Vec3::new(
-                            football.starting_position.x,
-                            flight_height,
-                            football.starting_position.z,
-                        )),
-                    )
-                };
-            transform.translation = start_with_height.lerp(
-                target_with_height,
-                football.current_movement_time / flight_time,
+        if football.has_landed {
+            continue;
+        }
+
+        football.velocity.y -= GRAVITY * time.delta_seconds();
+        let current = transform.translation;
+        let mut new = current + (football.velocity * time.delta_seconds());
+        let mut velocity = football.velocity;
+        if new.y < CORN_HEIGHT {
+            collidables.deflect(
+                &current,
+                &mut new,
+                &mut velocity,
+                FOOTBALL_RADIUS,
+                CORN_RESTITUTION,
             );
-            transform.rotate_x(time.delta_seconds());
-            transform.rotate_y(time.delta_seconds() / 2.0);
-            transform.rotate_z(time.delta_seconds() / 3.0);
-            football.current_movement_time += time.delta_seconds();
+        }
 
-            if football.current_movement_time >= flight_time {
-                football.current_movement_time = 0.0;
+        if new.y <= 0.0 && velocity.y < 0.0 {
+            new.y = 0.0;
+            football.has_bounced = true;
+            velocity.y = -velocity.y * BOUNCE_RESTITUTION;
+            let wobble = Quat::from_rotation_y(rng.gen_range(-BOUNCE_WOBBLE..BOUNCE_WOBBLE));
+            let rolling = wobble * Vec3::new(velocity.x, 0.0, velocity.z) * BOUNCE_FRICTION;
+            velocity.x = rolling.x;
+            velocity.z = rolling.z;
+
+            spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                particles::ParticleKind::Dust,
+                new,
+            ));
+            if velocity.y < SETTLE_SPEED {
                 football.has_landed = true;
+                velocity = Vec3::ZERO;
                 transform.rotation = Quat::IDENTITY;
+            } else {
+                audio.play_sfx_pitched(&game_assets.bounce, rng.gen_range(0.8..1.2));
             }
         }
+
+        transform.translation = new;
+        football.velocity = velocity;
+        if !football.has_landed {
+            // tumbles faster the faster it's going
+            let spin = time.delta_seconds() * (1.0 + (velocity.length() * 0.1));
+            transform.rotate_x(spin);
+            transform.rotate_y(spin / 2.0);
+            transform.rotate_z(spin / 3.0);
+        }
     }
 }
@@ -1,4 +1,4 @@
-use crate::{assets::GameAssets, player, LEFT_GOAL};
+use crate::{assets::GameAssets, player};
 use bevy::core_pipeline::clear_color::ClearColorConfig;
 use bevy::input::mouse::{MouseMotion, MouseWheel};
 use bevy::prelude::*;
@@ -39,25 +39,26 @@ pub fn follow_player(
     let camera_speed = 7.0;
     for mut camera_transform in cameras.iter_mut() {
         for player_transform in players.iter() {
-            if player_transform.rotation.y > 0.5{
-                let inverty = 0.49 - (player_transform.rotation.y-0.5);
-                camera_transform.translation.z += 
-                ((player_transform.translation.z - (inverty * 5.0)) - camera_transform.translation.z)
-                * camera_speed
-                * time.delta_seconds(); 
-            }
-            else if player_transform.rotation.y < -0.5{
-                let inverty = -0.49 - (player_transform.rotation.y+0.5);
-                camera_transform.translation.z += 
-                ((player_transform.translation.z - (inverty * 5.0)) - camera_transform.translation.z)
-                * camera_speed
-                * time.delta_seconds(); 
-            }
-            else{
-                camera_transform.translation.z += 
-                ((player_transform.translation.z - (player_transform.rotation.y.clamp(-0.65, 0.65) * 5.0)) - camera_transform.translation.z)
-                * camera_speed
-                * time.delta_seconds();
+            if player_transform.rotation.y > 0.5 {
+                let inverty = 0.49 - (player_transform.rotation.y - 0.5);
+                camera_transform.translation.z += ((player_transform.translation.z
+                    - (inverty * 5.0))
+                    - camera_transform.translation.z)
+                    * camera_speed
+                    * time.delta_seconds();
+            } else if player_transform.rotation.y < -0.5 {
+                let inverty = -0.49 - (player_transform.rotation.y + 0.5);
+                camera_transform.translation.z += ((player_transform.translation.z
+                    - (inverty * 5.0))
+                    - camera_transform.translation.z)
+                    * camera_speed
+                    * time.delta_seconds();
+            } else {
+                camera_transform.translation.z += ((player_transform.translation.z
+                    - (player_transform.rotation.y.clamp(-0.65, 0.65) * 5.0))
+                    - camera_transform.translation.z)
+                    * camera_speed
+                    * time.delta_seconds();
             }
         }
     }
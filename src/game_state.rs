@@ -1,4 +1,4 @@
-use crate::{football, player, AppState};
+use crate::{extra_point, football, maze_file, player, rounds, AppState};
 use bevy::prelude::*;
 
 pub struct GameStatePlugin;
@@ -19,9 +19,14 @@ pub struct LevelOverCleanupMarker;
 
 pub struct GameState {
     pub score: usize,
+    // everything scored in the rounds before this one
+    pub run_score: usize,
     pub shadows_on: bool,
     pub graphics_high: bool,
-    pub maze_size: f32,
+    pub combine_warning_on: bool,
+    // otherwise the landing marker only shows while the ball is on screen
+    pub landing_marker_always: bool,
+    pub maze_seed: u64,
     pub is_latest: bool,
     pub touchdown_on_leftside: bool,
     pub attached_enemies: usize,
@@ -30,23 +35,35 @@ pub struct GameState {
     pub corn_spawned: bool,
     pub death_count: usize,
     pub current_round: usize,
+    // set while a maze from the editor is being played
+    pub test_maze: Option<Handle<maze_file::MazeFile>>,
 }
 
 impl GameState {
-    pub fn initialize(graphics: bool, shadows_on: bool, game_version: bool) -> Self {
+    pub fn initialize(
+        graphics: bool,
+        shadows_on: bool,
+        game_version: bool,
+        combine_warning_on: bool,
+        landing_marker_always: bool,
+    ) -> Self {
         GameState {
             score: 0,
+            run_score: 0,
             shadows_on: shadows_on,
             graphics_high: graphics,
+            combine_warning_on,
+            landing_marker_always,
             attached_enemies: 0,
             is_latest: game_version,
-            maze_size: 80.0,
+            maze_seed: rand::random(),
             touchdown_on_leftside: false,
             corn_spawned: false,
             title_screen_cooldown: 1.0,
             enemies_spawned: false,
             current_round: 0,
             death_count: 0,
+            test_maze: None,
         }
     }
 }
@@ -55,32 +72,57 @@ impl Default for GameState {
     fn default() -> Self {
         GameState {
             score: 0,
+            run_score: 0,
             shadows_on: true,
             graphics_high: true,
+            combine_warning_on: true,
+            landing_marker_always: true,
             attached_enemies: 0,
             enemies_spawned: false,
             is_latest: true,
-            maze_size: 80.0,
+            maze_seed: rand::random(),
             corn_spawned: false,
             title_screen_cooldown: 1.0,
             death_count: 0,
             current_round: 0,
             touchdown_on_leftside: false,
+            test_maze: None,
         }
     }
 }
 
-pub struct TouchdownEvent;
+pub struct TouchdownEvent {
+    pub football: football::FootballKind,
+}
 
 fn handle_touchdown_event(
     mut touchdown_event_reader: EventReader<TouchdownEvent>,
     mut game_state: ResMut<GameState>,
+    rounds: Res<rounds::Rounds>,
     mut football_launch_event_writer: EventWriter<football::LaunchFootballEvent>,
+    mut extra_point_event_writer: EventWriter<extra_point::ExtraPointEvent>,
 ) {
-    for _event in touchdown_event_reader.iter() {
-        game_state.score += 100;
+    // the points themselves are worked out in scoring
+    for event in touchdown_event_reader.iter() {
+        let extra_points = rounds
+            .get(game_state.current_round)
+            .is_some_and(|round| round.extra_points.enabled);
+        // the extra point attempt flips sides and kicks the next ball out once it's over
+        if event.football == football::FootballKind::Regular && extra_points {
+            extra_point_event_writer.send(extra_point::ExtraPointEvent);
+            continue;
+        }
+
         game_state.touchdown_on_leftside = !game_state.touchdown_on_leftside;
-        football_launch_event_writer.send(football::LaunchFootballEvent);
+        // bonus balls don't come back
+        if matches!(
+            event.football,
+            football::FootballKind::Regular | football::FootballKind::Conversion
+        ) {
+            football_launch_event_writer.send(football::LaunchFootballEvent::new(
+                football::FootballKind::Regular,
+            ));
+        }
     }
 }
 
@@ -0,0 +1,624 @@
+use crate::{
+    asset_loading, assets::GameAssets, audio::GameAudio, cleanup, game_controller, game_state,
+    menus, title_screen::MenuAction, ui::text_size, AppState,
+};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Keeps the best runs for each mode and difficulty on disk (or in local storage on the web)
+/// and shows them from the title screen and after the last round.
+pub struct HighScorePlugin;
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HighScores::load())
+            .insert_resource(HighScoreScreen::default())
+            .add_system_set(
+                SystemSet::on_enter(AppState::HighScores)
+                    .with_system(setup)
+                    .with_system(game_controller::clear_presses),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::HighScores)
+                    .with_system(update_screen.after(handle_controllers))
+                    .with_system(
+                        handle_controllers.after(game_controller::store_controller_inputs),
+                    ),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::HighScores)
+                    .with_system(cleanup::<CleanupMarker>)
+                    .with_system(game_controller::clear_presses),
+            );
+    }
+}
+
+const MAX_ENTRIES: usize = 10;
+const INITIALS_LENGTH: usize = 3;
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_FILE: &str = "high_scores.ron";
+#[cfg(target_arch = "wasm32")]
+const STORAGE_KEY: &str = "football_combine_high_scores";
+
+#[derive(Component)]
+struct CleanupMarker;
+
+#[derive(Component)]
+enum ScreenText {
+    Heading,
+    Table,
+    Prompt,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Latest,
+    Jam,
+}
+
+/// Part of every table's key so saved scores keep working once there's a difficulty setting,
+/// until then everything is played on normal.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    Normal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Category {
+    pub mode: Mode,
+    pub difficulty: Difficulty,
+}
+
+impl Category {
+    pub const ALL: [Category; 2] = [
+        Category::new(Mode::Latest, Difficulty::Normal),
+        Category::new(Mode::Jam, Difficulty::Normal),
+    ];
+
+    pub const fn new(mode: Mode, difficulty: Difficulty) -> Self {
+        Category { mode, difficulty }
+    }
+
+    pub fn from_game_state(game_state: &game_state::GameState) -> Self {
+        let mode = if game_state.is_latest {
+            Mode::Latest
+        } else {
+            Mode::Jam
+        };
+        Category::new(mode, Difficulty::Normal)
+    }
+
+    fn label(&self) -> String {
+        let mode = match self.mode {
+            Mode::Latest => "LATEST",
+            Mode::Jam => "JAM",
+        };
+        let difficulty = match self.difficulty {
+            Difficulty::Normal => "NORMAL",
+        };
+        format!("{} / {}", mode, difficulty)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Entry {
+    pub initials: String,
+    pub score: usize,
+    pub round: usize,
+    pub date: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct HighScores {
+    tables: Vec<(Category, Vec<Entry>)>,
+}
+
+impl HighScores {
+    pub fn entries(&self, category: Category) -> &[Entry] {
+        self.tables
+            .iter()
+            .find(|(table_category, _)| *table_category == category)
+            .map(|(_, entries)| entries.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn qualifies(&self, category: Category, score: usize) -> bool {
+        let entries = self.entries(category);
+        score > 0
+            && (entries.len() < MAX_ENTRIES || entries.iter().any(|entry| entry.score < score))
+    }
+
+    /// Adds the entry in order and returns where it landed, if it made the table at all.
+    /// Ties go to whoever got there first.
+    pub fn insert(&mut self, category: Category, entry: Entry) -> Option<usize> {
+        if !self.qualifies(category, entry.score) {
+            return None;
+        }
+
+        let index = match self
+            .tables
+            .iter()
+            .position(|(table_category, _)| *table_category == category)
+        {
+            Some(index) => index,
+            None => {
+                self.tables.push((category, vec![]));
+                self.tables.len() - 1
+            }
+        };
+        let entries = &mut self.tables[index].1;
+        let rank = entries
+            .iter()
+            .position(|existing| existing.score < entry.score)
+            .unwrap_or(entries.len());
+        entries.insert(rank, entry);
+        entries.truncate(MAX_ENTRIES);
+
+        Some(rank)
+    }
+
+    fn load() -> Self {
+        read_saved()
+            .and_then(|text| ron::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = ron::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|text| write_saved(&text));
+        if let Err(e) = result {
+            println!("couldn't save high scores: {}", e);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_path() -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+
+    let data_directory = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+
+    data_directory.map(|directory| directory.join("football_combine").join(SAVE_FILE))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_saved() -> Option<String> {
+    std::fs::read_to_string(save_path()?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_saved(text: &str) -> Result<(), String> {
+    let path = save_path().ok_or("no data directory")?;
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_saved() -> Option<String> {
+    local_storage()?.get_item(STORAGE_KEY).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_saved(text: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("no local storage")?
+        .set_item(STORAGE_KEY, text)
+        .map_err(|e| format!("{:?}", e))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn today() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (year, month, day) = date_from_days((seconds / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn today() -> String {
+    let date = js_sys::Date::new_0();
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date()
+    )
+}
+
+/// Turns days since 1970-01-01 into a calendar date.
+#[cfg(not(target_arch = "wasm32"))]
+fn date_from_days(days: i64) -> (i64, u32, u32) {
+    // shift the epoch to 0000-03-01 so leap days land at the end of each year
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month as u32, day as u32)
+}
+
+/// A finished run waiting to be put on the board.
+pub struct PendingScore {
+    pub category: Category,
+    pub score: usize,
+    pub round: usize,
+}
+
+#[derive(Default)]
+pub struct HighScoreScreen {
+    pending: Option<PendingScore>,
+    is_entering: bool,
+    initials: [u8; INITIALS_LENGTH],
+    cursor: usize,
+    category: usize,
+    highlight: Option<usize>,
+}
+
+impl HighScoreScreen {
+    /// Hands the run that just ended to the high score screen.
+    pub fn finish_run(&mut self, game_state: &game_state::GameState) {
+        self.pending = Some(PendingScore {
+            category: Category::from_game_state(game_state),
+            score: game_state.run_score,
+            round: game_state.current_round,
+        });
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    high_scores: Res<HighScores>,
+    mut screen: ResMut<HighScoreScreen>,
+    mut clear_color: ResMut<ClearColor>,
+    text_scaler: text_size::TextScaler,
+) {
+    clear_color.0 = Color::hex("00068a").unwrap();
+
+    screen.is_entering = false;
+    screen.highlight = None;
+    screen.cursor = 0;
+    screen.initials = [b'A'; INITIALS_LENGTH];
+    if let Some(pending) = &screen.pending {
+        let category = Category::ALL
+            .iter()
+            .position(|category| *category == pending.category)
+            .unwrap_or(0);
+        screen.is_entering = high_scores.qualifies(pending.category, pending.score);
+        screen.category = category;
+    }
+    if !screen.is_entering {
+        screen.pending = None;
+    }
+
+    commands
+        .spawn_bundle(Camera2dBundle::default())
+        .insert(CleanupMarker);
+
+    commands
+        .spawn_bundle(InputManagerBundle {
+            input_map: MenuAction::default_input_map(),
+            action_state: ActionState::default(),
+        })
+        .insert(CleanupMarker);
+
+    let text = |text: ScreenText, top: f32, font_size: f32, color: Color| {
+        (
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: text_scaler.scale(font_size),
+                    color,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Percent(top),
+                    left: Val::Percent(20.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            text,
+        )
+    };
+
+    for (bundle, marker) in [
+        text(
+            ScreenText::Heading,
+            5.0,
+            menus::DEFAULT_FONT_SIZE * 0.7,
+            Color::WHITE,
+        ),
+        text(
+            ScreenText::Table,
+            20.0,
+            menus::BUTTON_LABEL_FONT_SIZE,
+            Color::WHITE,
+        ),
+        text(
+            ScreenText::Prompt,
+            85.0,
+            menus::BUTTON_LABEL_FONT_SIZE,
+            Color::rgb(1.0, 0.8, 0.2),
+        ),
+    ] {
+        commands
+            .spawn_bundle(bundle)
+            .insert(marker)
+            .insert(CleanupMarker);
+    }
+}
+
+fn update_screen(
+    action_state: Query<&ActionState<MenuAction>>,
+    mut screen: ResMut<HighScoreScreen>,
+    mut high_scores: ResMut<HighScores>,
+    mut texts: Query<(&mut Text, &ScreenText)>,
+    mut assets_handler: asset_loading::AssetsHandler,
+    mut game_assets: ResMut<GameAssets>,
+    mut game_state: ResMut<game_state::GameState>,
+    mut audio: GameAudio,
+) {
+    let action_state = match action_state.get_single() {
+        Ok(action_state) => action_state,
+        Err(_) => return,
+    };
+
+    if screen.is_entering {
+        let cursor = screen.cursor;
+        if action_state.just_pressed(MenuAction::Up) {
+            audio.play_sfx(&game_assets.blip);
+            screen.initials[cursor] = if screen.initials[cursor] == b'Z' {
+                b'A'
+            } else {
+                screen.initials[cursor] + 1
+            };
+        }
+        if action_state.just_pressed(MenuAction::Down) {
+            audio.play_sfx(&game_assets.blip);
+            screen.initials[cursor] = if screen.initials[cursor] == b'A' {
+                b'Z'
+            } else {
+                screen.initials[cursor] - 1
+            };
+        }
+        if action_state.just_pressed(MenuAction::Left) {
+            audio.play_sfx(&game_assets.blip);
+            screen.cursor = cursor.saturating_sub(1);
+        }
+        if action_state.just_pressed(MenuAction::Right) {
+            audio.play_sfx(&game_assets.blip);
+            screen.cursor = (cursor + 1).min(INITIALS_LENGTH - 1);
+        }
+        if action_state.just_pressed(MenuAction::Select) {
+            audio.play_sfx(&game_assets.blip);
+            if cursor < INITIALS_LENGTH - 1 {
+                screen.cursor += 1;
+            } else if let Some(pending) = screen.pending.take() {
+                let entry = Entry {
+                    initials: String::from_utf8_lossy(&screen.initials).to_string(),
+                    score: pending.score,
+                    round: pending.round,
+                    date: today(),
+                };
+                screen.highlight = high_scores.insert(pending.category, entry);
+                screen.is_entering = false;
+                high_scores.save();
+            }
+        }
+    } else {
+        if action_state.just_pressed(MenuAction::Left) {
+            audio.play_sfx(&game_assets.blip);
+            screen.highlight = None;
+            screen.category = screen
+                .category
+                .checked_sub(1)
+                .unwrap_or(Category::ALL.len() - 1);
+        }
+        if action_state.just_pressed(MenuAction::Right) {
+            audio.play_sfx(&game_assets.blip);
+            screen.highlight = None;
+            screen.category = (screen.category + 1) % Category::ALL.len();
+        }
+        if action_state.just_pressed(MenuAction::Select) {
+            audio.play_sfx(&game_assets.blip);
+            // otherwise the title screen sees the same press
+            game_state.title_screen_cooldown = 0.5;
+            assets_handler.load(AppState::TitleScreen, &mut game_assets, &game_state);
+            return;
+        }
+    }
+
+    let category = Category::ALL[screen.category];
+    for (mut text, screen_text) in &mut texts {
+        text.sections[0].value = match screen_text {
+            ScreenText::Heading => format!("HIGH SCORES  < {} >", category.label()),
+            ScreenText::Table => {
+                let entries = high_scores.entries(category);
+                if entries.is_empty() {
+                    "NO SCORES YET".to_string()
+                } else {
+                    entries
+                        .iter()
+                        .enumerate()
+                        .map(|(i, entry)| {
+                            format!(
+                                "{}{:>2}. {:<4}{:>8}   ROUND {}   {}\n",
+                                if Some(i) == screen.highlight {
+                                    ">"
+                                } else {
+                                    " "
+                                },
+                                i + 1,
+                                entry.initials,
+                                entry.score,
+                                entry.round,
+                                entry.date
+                            )
+                        })
+                        .collect()
+                }
+            }
+            ScreenText::Prompt => match &screen.pending {
+                Some(pending) if screen.is_entering => {
+                    let initials = screen
+                        .initials
+                        .iter()
+                        .enumerate()
+                        .map(|(i, letter)| {
+                            if i == screen.cursor {
+                                format!("[{}]", *letter as char)
+                            } else {
+                                format!(" {} ", *letter as char)
+                            }
+                        })
+                        .collect::<String>();
+                    format!(
+                        "NEW HIGH SCORE {}!  ENTER INITIALS {}",
+                        pending.score, initials
+                    )
+                }
+                _ => "PRESS SELECT TO RETURN".to_string(),
+            },
+        };
+    }
+}
+
+fn handle_controllers(
+    controllers: Res<game_controller::GameController>,
+    mut players: Query<(Entity, &mut ActionState<MenuAction>)>,
+) {
+    for (_, mut action_state) in players.iter_mut() {
+        for (_, just_pressed) in controllers.just_pressed.iter() {
+            action_state.release(MenuAction::Up);
+            action_state.release(MenuAction::Down);
+            action_state.release(MenuAction::Left);
+            action_state.release(MenuAction::Right);
+
+            action_state.release(MenuAction::Select);
+
+            if just_pressed.contains(&game_controller::GameButton::Up) {
+                action_state.press(MenuAction::Up);
+            }
+            if just_pressed.contains(&game_controller::GameButton::Down) {
+                action_state.press(MenuAction::Down);
+            }
+            if just_pressed.contains(&game_controller::GameButton::Left) {
+                action_state.press(MenuAction::Left);
+            }
+            if just_pressed.contains(&game_controller::GameButton::Right) {
+                action_state.press(MenuAction::Right);
+            }
+            if just_pressed.contains(&game_controller::GameButton::ActionDown)
+                || just_pressed.contains(&game_controller::GameButton::Start)
+            {
+                action_state.press(MenuAction::Select);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATEGORY: Category = Category::new(Mode::Latest, Difficulty::Normal);
+
+    fn entry(initials: &str, score: usize) -> Entry {
+        Entry {
+            initials: initials.to_string(),
+            score,
+            round: 1,
+            date: "2022-08-01".to_string(),
+        }
+    }
+
+    #[test]
+    fn entries_stay_sorted_and_ties_keep_the_older_one_first() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.insert(CATEGORY, entry("AAA", 100)), Some(0));
+        assert_eq!(high_scores.insert(CATEGORY, entry("BBB", 300)), Some(0));
+        assert_eq!(high_scores.insert(CATEGORY, entry("CCC", 100)), Some(2));
+
+        let initials = high_scores
+            .entries(CATEGORY)
+            .iter()
+            .map(|entry| entry.initials.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(initials, vec!["BBB", "AAA", "CCC"]);
+    }
+
+    #[test]
+    fn full_table_only_takes_better_scores() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=MAX_ENTRIES {
+            high_scores.insert(CATEGORY, entry("AAA", score * 10));
+        }
+
+        assert!(!high_scores.qualifies(CATEGORY, 10));
+        assert_eq!(high_scores.insert(CATEGORY, entry("BBB", 5)), None);
+        assert_eq!(
+            high_scores.insert(CATEGORY, entry("BBB", 15)),
+            Some(MAX_ENTRIES - 1)
+        );
+        assert_eq!(high_scores.entries(CATEGORY).len(), MAX_ENTRIES);
+        assert_eq!(high_scores.entries(CATEGORY).last().unwrap().score, 15);
+    }
+
+    #[test]
+    fn categories_are_kept_apart() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(CATEGORY, entry("AAA", 100));
+
+        let jam = Category::new(Mode::Jam, Difficulty::Normal);
+        assert!(high_scores.entries(jam).is_empty());
+        assert!(!high_scores.qualifies(jam, 0));
+    }
+
+    #[test]
+    fn saved_table_reads_back() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(CATEGORY, entry("AAA", 100));
+
+        let text = ron::to_string(&high_scores).unwrap();
+        let loaded: HighScores = ron::from_str(&text).unwrap();
+        assert_eq!(loaded.entries(CATEGORY), high_scores.entries(CATEGORY));
+    }
+
+    #[test]
+    fn days_become_dates() {
+        assert_eq!(date_from_days(0), (1970, 1, 1));
+        assert_eq!(date_from_days(11_016), (2000, 2, 29));
+        assert_eq!(date_from_days(19_205), (2022, 8, 1));
+    }
+}
@@ -1,8 +1,7 @@
 use crate::{
     asset_loading, assets::GameAssets, audio::GameAudio, banter, cleanup, collision, combine,
     component_adder, component_adder::AnimationLink, cutscene, enemy, football, game_camera,
-    game_state, maze, player, AppState, BOTTOM_END, LEFT_END, LEFT_GOAL, RIGHT_END, RIGHT_GOAL,
-    TOP_END,
+    game_state, maze, maze_file, maze_generator, particles, player, rounds, AppState,
 };
 use bevy::gltf::Gltf;
 use bevy::prelude::*;
@@ -97,12 +96,17 @@ pub fn load(
     assets_handler.add_audio(&mut game_assets.bounce, "audio/bounce.wav");
     assets_handler.add_audio(&mut game_assets.football_pop, "audio/football_pop.wav");
     assets_handler.add_audio(&mut game_assets.bill_speak, "audio/bill_speak.wav");
-    assets_handler.add_audio(&mut game_assets.bgm, "audio/combine.ogg");
 
-    match game_state.current_round {
-        1 => assets_handler.add_glb(&mut game_assets.maze, "models/maze_01.glb"),
-        2 => assets_handler.add_glb(&mut game_assets.maze, "models/maze_01.glb"),
-        _ => assets_handler.add_glb(&mut game_assets.maze, "models/maze.glb"),
+    if let Some(round) = assets_handler.rounds.get(game_state.current_round).cloned() {
+        assets_handler.add_audio(&mut game_assets.bgm, &round.music);
+        match maze::MazeSource::for_round(game_state, &round) {
+            maze::MazeSource::Model(path) => assets_handler.add_glb(&mut game_assets.maze, &path),
+            maze::MazeSource::File(path) => {
+                assets_handler.add_maze_file(&mut game_assets.maze_file, &path)
+            }
+            // the editor already put its maze in maze_file
+            maze::MazeSource::Generated { .. } | maze::MazeSource::Editor => (),
+        }
     }
 
     assets_handler.add_material(&mut game_assets.bill_icon, "textures/bill.png", true);
@@ -119,7 +123,6 @@ pub fn load(
         &mut game_assets.blood_mesh,
         Mesh::from(shape::Plane::default()),
     );
-    assets_handler.add_animation(&mut game_assets.corn_sway, "models/corn.glb#Animation0");
     assets_handler.add_standard_material(
         &mut game_assets.corn_stalk_material,
         StandardMaterial {
@@ -154,6 +157,81 @@ pub fn load(
 
     assets_handler.add_material(&mut game_assets.blood, "textures/blood.png", true);
 
+    assets_handler.add_standard_mesh(
+        &mut game_assets.particle_mesh,
+        Mesh::from(shape::Cube { size: 1.0 }),
+    );
+    assets_handler.add_standard_material(
+        &mut game_assets.chaff_material,
+        StandardMaterial {
+            base_color: Color::rgb(0.85, 0.75, 0.35),
+            perceptual_roughness: 1.0,
+            ..Default::default()
+        },
+    );
+    assets_handler.add_standard_material(
+        &mut game_assets.dust_material,
+        StandardMaterial {
+            unlit: true,
+            base_color: Color::rgba(0.55, 0.45, 0.3, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            ..Default::default()
+        },
+    );
+    assets_handler.add_standard_material(
+        &mut game_assets.smoke_material,
+        StandardMaterial {
+            unlit: true,
+            base_color: Color::rgba(0.2, 0.2, 0.2, 0.6),
+            alpha_mode: AlphaMode::Blend,
+            ..Default::default()
+        },
+    );
+    assets_handler.add_standard_material(
+        &mut game_assets.wheel_rut_material,
+        StandardMaterial {
+            base_color: Color::rgb(0.3, 0.2, 0.1),
+            perceptual_roughness: 1.0,
+            ..Default::default()
+        },
+    );
+    assets_handler.add_standard_material(
+        &mut game_assets.hay_bale_material,
+        StandardMaterial {
+            base_color: Color::rgb(0.85, 0.7, 0.3),
+            perceptual_roughness: 1.0,
+            ..Default::default()
+        },
+    );
+    assets_handler.add_standard_mesh(
+        &mut game_assets.landing_marker_mesh,
+        Mesh::from(shape::Torus {
+            radius: 0.5,
+            ring_radius: 0.04,
+            subdivisions_segments: 32,
+            subdivisions_sides: 4,
+        }),
+    );
+    assets_handler.add_standard_material(
+        &mut game_assets.landing_marker_material,
+        StandardMaterial {
+            unlit: true,
+            base_color: Color::rgba(1.0, 0.9, 0.2, 0.8),
+            alpha_mode: AlphaMode::Blend,
+            ..Default::default()
+        },
+    );
+    game_assets.confetti_materials = particles::CONFETTI_COLORS
+        .iter()
+        .map(|color| {
+            assets_handler.materials.add(StandardMaterial {
+                unlit: true,
+                base_color: *color,
+                ..Default::default()
+            })
+        })
+        .collect();
+
     let size = Extent3d {
         width: RENDER_TEXTURE_SIZE,
         height: RENDER_TEXTURE_SIZE,
@@ -194,6 +272,7 @@ pub fn setup(
     game_assets: Res<GameAssets>,
     asset_server: Res<AssetServer>,
     assets_gltf: Res<Assets<Gltf>>,
+    maze_files: Res<Assets<maze_file::MazeFile>>,
     mut game_state: ResMut<game_state::GameState>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
@@ -202,6 +281,7 @@ pub fn setup(
     mut banter_state: ResMut<banter::BanterState>,
     cutscene_state: Res<cutscene::CutsceneState>,
     mut football_launch_event_writer: EventWriter<football::LaunchFootballEvent>,
+    rounds: Res<rounds::Rounds>,
     mut camera: Query<&mut Transform, With<game_camera::PanOrbitCamera>>,
     corn_stalks: Query<(&maze::CornStalk, &Transform), Without<game_camera::PanOrbitCamera>>,
 ) {
@@ -210,9 +290,14 @@ pub fn setup(
     game_state.attached_enemies = 0;
     game_state.enemies_spawned = false;
     game_state.touchdown_on_leftside = false;
+    let round = rounds
+        .get(game_state.current_round)
+        .cloned()
+        .unwrap_or_default();
+    let field = round.field;
 
-    match game_state.current_round {
-        1 => {
+    match round.lighting {
+        rounds::LightingPreset::Sunset => {
             // lights
             commands.insert_resource(AmbientLight {
                 color: Color::WHITE,
@@ -249,7 +334,7 @@ pub fn setup(
                 })
                 .insert(CleanupMarker);
         }
-        2 => {
+        rounds::LightingPreset::Night => {
             // lights
             commands.insert_resource(AmbientLight {
                 color: Color::ALICE_BLUE,
@@ -257,8 +342,8 @@ pub fn setup(
             });
             commands
                 .spawn_bundle(SpotLightBundle {
-                    transform: Transform::from_xyz(0.0, 15.0, RIGHT_GOAL)
-                        .looking_at(Vec3::new(0.0, 0.0, LEFT_GOAL), Vec3::Y),
+                    transform: Transform::from_xyz(0.0, 15.0, field.right_goal)
+                        .looking_at(Vec3::new(0.0, 0.0, field.left_goal), Vec3::Y),
                     spot_light: SpotLight {
                         intensity: 10000.0, // lumens
                         color: Color::WHITE,
@@ -274,8 +359,8 @@ pub fn setup(
                 .insert(CleanupMarker);
             commands
                 .spawn_bundle(SpotLightBundle {
-                    transform: Transform::from_xyz(0.0, 15.0, LEFT_GOAL)
-                        .looking_at(Vec3::new(0.0, 0.0, LEFT_GOAL), Vec3::Y),
+                    transform: Transform::from_xyz(0.0, 15.0, field.left_goal)
+                        .looking_at(Vec3::new(0.0, 0.0, field.left_goal), Vec3::Y),
                     spot_light: SpotLight {
                         intensity: 10000.0, // lumens
                         color: Color::WHITE,
@@ -290,7 +375,7 @@ pub fn setup(
                 })
                 .insert(CleanupMarker);
         }
-        _ => {
+        rounds::LightingPreset::Day => {
             // lights
             commands.insert_resource(AmbientLight {
                 color: Color::WHITE,
@@ -335,7 +420,7 @@ pub fn setup(
             .spawn_bundle(SceneBundle {
                 scene: gltf.scenes[0].clone(),
                 transform: {
-                    let mut t = Transform::from_xyz(0.0, 0.0, LEFT_GOAL);
+                    let mut t = Transform::from_translation(field.player_start());
                     t.rotate_y(TAU * 0.75);
                     t
                 },
@@ -372,7 +457,7 @@ pub fn setup(
             .spawn_bundle(SceneBundle {
                 scene: gltf.scenes[0].clone(),
                 transform: {
-                    let mut t = Transform::from_xyz(6.976, 0.0, -48.0);
+                    let mut t = Transform::from_xyz(6.976, 0.0, field.left_end - 0.5);
                     t.rotation = Quat::from_rotation_y(TAU * 0.75);
                     t
                 },
@@ -384,7 +469,7 @@ pub fn setup(
             .spawn_bundle(SceneBundle {
                 scene: gltf.scenes[0].clone(),
                 transform: {
-                    let mut t = Transform::from_xyz(6.976, 0.0, 48.0);
+                    let mut t = Transform::from_xyz(6.976, 0.0, field.right_end + 1.0);
                     t.rotation = Quat::from_rotation_y(TAU * 0.25);
                     t
                 },
@@ -394,47 +479,104 @@ pub fn setup(
             .insert(Name::new("Kicker2"));
     }
 
+    let maze_area = field.maze_area();
+    let maze_source = maze::MazeSource::for_round(&game_state, &round);
+    let maze_hints = match maze_source {
+        maze::MazeSource::File(_) | maze::MazeSource::Editor => maze_files
+            .get(&game_assets.maze_file)
+            .map(|maze_file| maze_file.hints(maze_area.0, maze_area.1))
+            .unwrap_or_default(),
+        _ => maze::MazeHints::default(),
+    };
+
     if let Some(gltf) = assets_gltf.get(&game_assets.combine.clone()) {
-        let combine_position = if game_state.corn_spawned && corn_stalks.iter().len() > 0 {
-            let unharvested_corn = corn_stalks
-                .iter()
-                .filter(|(c, _)| !c.is_harvested)
-                .collect::<Vec<_>>();
-            let mut rng = thread_rng();
-            let corn_transform = unharvested_corn.choose(&mut rng).map(|(_, t)| *t);
-            let starting_row = if let Some(corn_transform) = corn_transform {
-                corn_transform.translation.x
+        let mut rng = thread_rng();
+        let (_, start_z) = field.combine_turnaround();
+        for i in 0..round.combine_count {
+            // spread extra combines out across the field
+            let default_row = field.top_end * 0.5
+                + ((field.bottom_end - field.top_end) * 0.5 * i as f32
+                    / (round.combine_count.max(2) - 1) as f32);
+            let combine_position = if let Some(row) = maze_hints.combine_rows.get(i) {
+                Transform::from_xyz(*row, 0.0, start_z)
+            } else if game_state.corn_spawned && corn_stalks.iter().len() > 0 {
+                let unharvested_corn = corn_stalks
+                    .iter()
+                    .filter(|(c, _)| !c.is_harvested && c.kind.is_harvestable())
+                    .collect::<Vec<_>>();
+                let corn_transform = unharvested_corn.choose(&mut rng).map(|(_, t)| *t);
+                let starting_row = if let Some(corn_transform) = corn_transform {
+                    corn_transform.translation.x
+                } else {
+                    default_row
+                };
+                Transform::from_xyz(starting_row, 0.0, start_z)
             } else {
-                TOP_END * 0.5
+                Transform::from_xyz(default_row, 0.0, start_z)
             };
-            Transform::from_xyz(starting_row, 0.0, (game_state.maze_size / 2.0))
-        } else {
-            Transform::from_xyz(TOP_END * 0.5, 0.0, (game_state.maze_size / 2.0))
-        };
-        commands
-            .spawn_bundle(SceneBundle {
-                scene: gltf.scenes[0].clone(),
-                transform: {
-                    let mut t = combine_position;
-                    t.rotate_y(TAU * 0.25);
-                    t
-                },
 
-                ..default()
-            })
-            .insert(combine::Combine::create(game_state.is_latest))
-            .insert(AnimationLink { entity: None })
-            .insert(CleanupMarker)
-            .insert(Name::new("Combine"));
+            let mut combine = combine::Combine::create(game_state.is_latest);
+            combine.speed *= round.combine_speed;
+            commands
+                .spawn_bundle(SceneBundle {
+                    scene: gltf.scenes[0].clone(),
+                    transform: {
+                        let mut t = combine_position;
+                        t.rotate_y(TAU * 0.25);
+                        t
+                    },
+
+                    ..default()
+                })
+                .insert(combine)
+                .insert(AnimationLink { entity: None })
+                .insert(CleanupMarker)
+                .insert(Name::new("Combine"));
+        }
     }
 
-    if let Some(gltf) = assets_gltf.get(&game_assets.maze.clone()) {
-        commands
-            .spawn_bundle(SceneBundle {
-                scene: gltf.scenes[0].clone(),
-                ..default()
-            })
-            .insert(CleanupMarker);
+    commands.insert_resource(maze_hints);
+    match maze_source {
+        maze::MazeSource::Model(_) => {
+            if let Some(gltf) = assets_gltf.get(&game_assets.maze.clone()) {
+                commands
+                    .spawn_bundle(SceneBundle {
+                        scene: gltf.scenes[0].clone(),
+                        ..default()
+                    })
+                    .insert(CleanupMarker);
+            }
+        }
+        maze::MazeSource::File(_) | maze::MazeSource::Editor => {
+            if !game_state.corn_spawned {
+                if let Some(maze_file) = maze_files.get(&game_assets.maze_file) {
+                    let layout = maze_file.to_layout(maze_area.0, maze_area.1);
+                    maze::spawn_maze_markers(&mut commands, &layout, CleanupMarker);
+                }
+
+                // there's no intro cutscene to do this when test playing
+                if game_state.test_maze.is_some() {
+                    game_state.corn_spawned = true;
+                    audio.play_bgm(&game_assets.bgm);
+                }
+            }
+        }
+        maze::MazeSource::Generated {
+            corridor_width,
+            wall_width,
+        } => {
+            if !game_state.corn_spawned {
+                let maze_settings = maze_generator::MazeSettings {
+                    seed: game_state
+                        .maze_seed
+                        .wrapping_add(game_state.current_round as u64),
+                    corridor_width,
+                    wall_width,
+                };
+                let layout = maze_generator::generate(maze_settings, maze_area.0, maze_area.1);
+                maze::spawn_maze_markers(&mut commands, &layout, CleanupMarker);
+            }
+        }
     }
 
     component_adder.reset();
@@ -448,7 +590,7 @@ pub fn setup(
                 Vec3::new(
                     game_camera::INGAME_CAMERA_X,
                     game_camera::INGAME_CAMERA_Y,
-                    LEFT_GOAL,
+                    field.left_goal,
                 ),
                 Quat::from_axis_angle(
                     game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -460,7 +602,7 @@ pub fn setup(
                 camera.translation = Vec3::new(
                     game_camera::INGAME_CAMERA_X,
                     game_camera::INGAME_CAMERA_Y,
-                    LEFT_GOAL,
+                    field.left_goal,
                 );
                 camera.rotation = Quat::from_axis_angle(
                     game_camera::INGAME_CAMERA_ROTATION_AXIS,
@@ -469,7 +611,9 @@ pub fn setup(
             }
         }
 
-        football_launch_event_writer.send(football::LaunchFootballEvent);
+        football_launch_event_writer.send(football::LaunchFootballEvent::new(
+            football::FootballKind::Regular,
+        ));
     } else if camera.iter().len() == 0 {
         game_camera::spawn_camera(
             &mut commands,
@@ -0,0 +1,102 @@
+use crate::{assets::GameAssets, football, game_camera, game_state, ingame, AppState};
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+
+/// Puts a ring on the ground where a kicked football is going to come down, shrinking as it
+/// gets closer to landing.
+pub struct LandingMarkerPlugin;
+impl Plugin for LandingMarkerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(spawn_landing_markers)
+                .with_system(update_landing_markers.after(football::move_football)),
+        );
+    }
+}
+
+// how wide the ring is right after the kick and right before the ball comes down
+const START_SIZE: f32 = 5.0;
+const END_SIZE: f32 = 1.0;
+
+#[derive(Component)]
+pub struct LandingMarker {
+    football: Entity,
+    pub target: Vec3,
+}
+
+fn spawn_landing_markers(
+    mut commands: Commands,
+    footballs: Query<(Entity, &football::Football), Added<football::Football>>,
+    game_assets: Res<GameAssets>,
+) {
+    for (entity, football) in &footballs {
+        // golden balls are just sitting there already
+        if football.has_landed {
+            continue;
+        }
+
+        commands
+            .spawn_bundle(PbrBundle {
+                mesh: game_assets.landing_marker_mesh.clone(),
+                material: game_assets.landing_marker_material.clone(),
+                transform: Transform::from_translation(football.target + (Vec3::Y * 0.05))
+                    .with_scale(Vec3::new(START_SIZE, 1.0, START_SIZE)),
+                ..default()
+            })
+            .insert(NotShadowCaster)
+            .insert(NotShadowReceiver)
+            .insert(LandingMarker {
+                football: entity,
+                target: football.target,
+            })
+            .insert(ingame::CleanupMarker)
+            .insert(Name::new("LandingMarker"));
+    }
+}
+
+fn update_landing_markers(
+    mut commands: Commands,
+    mut markers: Query<(Entity, &LandingMarker, &mut Transform, &mut Visibility)>,
+    footballs: Query<(&football::Football, &Transform), Without<LandingMarker>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<game_camera::PanOrbitCamera>>,
+    game_state: Res<game_state::GameState>,
+) {
+    for (entity, marker, mut transform, mut visibility) in &mut markers {
+        // picked up, chopped up or already bouncing around
+        let landing = footballs
+            .get(marker.football)
+            .ok()
+            .and_then(|(football, ball)| {
+                football
+                    .time_to_land(ball.translation.y)
+                    .map(|time| (time, ball.translation))
+            });
+        let (time_to_land, ball) = match landing {
+            Some(landing) => landing,
+            None => {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+        };
+
+        let size = END_SIZE + ((START_SIZE - END_SIZE) * (time_to_land / football::FLIGHT_TIME));
+        let size = size.clamp(END_SIZE, START_SIZE);
+        transform.scale = Vec3::new(size, 1.0, size);
+
+        visibility.is_visible = game_state.landing_marker_always
+            || cameras
+                .iter()
+                .any(|(camera, camera_transform)| is_on_screen(camera, camera_transform, ball));
+    }
+}
+
+fn is_on_screen(camera: &Camera, camera_transform: &GlobalTransform, position: Vec3) -> bool {
+    match (
+        camera.world_to_viewport(camera_transform, position),
+        camera.logical_viewport_size(),
+    ) {
+        (Some(p), Some(size)) => p.x >= 0.0 && p.x <= size.x && p.y >= 0.0 && p.y <= size.y,
+        _ => false,
+    }
+}
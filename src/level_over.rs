@@ -1,7 +1,7 @@
 use crate::{
     asset_loading, assets, assets::GameAssets, cleanup, component_adder::AnimationLink, cutscene,
-    football, game_camera, game_state, ingame, ingame_ui, menus, other_persons,
-    title_screen::MenuAction, ui::text_size, AppState, LEFT_GOAL,
+    football, game_camera, game_state, high_score, ingame, ingame_ui, menus, other_persons,
+    title_screen::MenuAction, ui::text_size, AppState,
 };
 use bevy::prelude::*;
 
@@ -21,17 +21,35 @@ fn load_next_level(
     mut game_state: ResMut<game_state::GameState>,
     mut game_assets: ResMut<assets::GameAssets>,
     mut cutscene_state: ResMut<cutscene::CutsceneState>,
+    mut high_score_screen: ResMut<high_score::HighScoreScreen>,
 ) {
+    game_state.run_score += game_state.score;
     game_state.score = 0;
     game_state.corn_spawned = false;
-    game_state.current_round += 1;
 
-    println!("setting up round {}", game_state.current_round);
-    match game_state.current_round {
-        1 => cutscene_state.init(cutscene::Cutscene::LevelTwoIntro),
-        2 => cutscene_state.init(cutscene::Cutscene::LevelThreeIntro),
-        _ => cutscene_state.init(cutscene::Cutscene::Intro),
+    // test plays go back to the editor instead of moving on
+    if game_state.test_maze.is_some() {
+        assets_handler.load(AppState::Editor, &mut game_assets, &game_state);
+        return;
     }
 
-    assets_handler.load(AppState::InGame, &mut game_assets, &game_state);
+    game_state.current_round += 1;
+
+    let intro = assets_handler
+        .rounds
+        .get(game_state.current_round)
+        .map(|round| round.intro);
+    match intro {
+        Some(intro) => {
+            println!("setting up round {}", game_state.current_round);
+            cutscene_state.init(intro);
+            assets_handler.load(AppState::InGame, &mut game_assets, &game_state);
+        }
+        None => {
+            // that was the last round
+            high_score_screen.finish_run(&game_state);
+            *game_state = game_state::GameState::default();
+            assets_handler.load(AppState::HighScores, &mut game_assets, &game_state);
+        }
+    }
 }
@@ -11,46 +11,64 @@ use bevy_inspector_egui::WorldInspectorPlugin;
 mod asset_loading;
 mod assets;
 mod audio;
+mod ball_carrier;
 mod banter;
 mod billboard;
+mod bonus;
 mod collision;
 mod combine;
+mod combine_warning;
 mod component_adder;
+mod corn_chunks;
 mod cutscene;
 mod direction;
+mod editor;
 mod enemy;
+mod extra_point;
+mod field;
 mod football;
 mod game_camera;
 mod game_controller;
 mod game_state;
+mod high_score;
 mod ingame;
 mod ingame_ui;
+mod landing_marker;
 mod level_over;
 mod maze;
+mod maze_file;
+mod maze_generator;
+mod maze_validation;
 mod menus;
 mod options;
 mod other_persons;
+mod particles;
+mod pathing;
 mod player;
+mod rounds;
+mod scoring;
 mod shaders;
+mod spatial_grid;
 mod splash;
+mod stats;
 mod title_screen;
+mod trample;
 mod ui;
 mod wasm;
 
-const LEFT_GOAL: f32 = -38.5;
-const RIGHT_GOAL: f32 = 37.5;
-const LEFT_END: f32 = -47.5;
-const RIGHT_END: f32 = 47.0;
-const BOTTOM_END: f32 = -19.471;
-const TOP_END: f32 = 20.471;
-
 fn main() {
+    // `validate-maze` checks mazes from the command line instead of starting the game
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(|arg| arg.as_str()) == Some("validate-maze") {
+        std::process::exit(maze_validation::run(&args[2..]));
+    }
+
     App::new()
-            //  .insert_resource(AssetServerSettings {
-            //      watch_for_changes: true,
-            //      ..default()
-            //  })
-            //  .add_plugin(LogDiagnosticsPlugin::default())
+        //  .insert_resource(AssetServerSettings {
+        //      watch_for_changes: true,
+        //      ..default()
+        //  })
+        //  .add_plugin(LogDiagnosticsPlugin::default())
         .insert_resource(LogSettings {
             filter: "info,wgpu_core=warn,wgpu_hal=warn".into(),
             level: bevy::log::Level::DEBUG,
@@ -60,26 +78,42 @@ fn main() {
         .add_plugin(WorldInspectorPlugin::new())
         .add_plugin(audio::GameAudioPlugin)
         .add_plugin(assets::AssetsPlugin)
+        .add_plugin(ball_carrier::BallCarrierPlugin)
         .add_plugin(banter::BanterPlugin)
         .add_plugin(cutscene::CutscenePlugin)
+        .add_plugin(editor::EditorPlugin)
         .add_plugin(asset_loading::AssetLoadingPlugin)
         .add_plugin(billboard::BillboardPlugin)
+        .add_plugin(bonus::BonusPlugin)
+        .add_plugin(collision::CollisionPlugin)
         .add_plugin(splash::SplashPlugin)
         .add_plugin(component_adder::ComponentAdderPlugin)
         .add_plugin(enemy::EnemyPlugin)
+        .add_plugin(extra_point::ExtraPointPlugin)
+        .add_plugin(field::FieldPlugin)
         .add_plugin(options::OptionsMenuPlugin)
         .add_plugin(level_over::LevelOverPlugin)
         .add_plugin(football::FootballPlugin)
         .add_plugin(combine::CombinePlugin)
+        .add_plugin(combine_warning::CombineWarningPlugin)
         .add_plugin(game_state::GameStatePlugin)
+        .add_plugin(high_score::HighScorePlugin)
         .add_plugin(ingame_ui::InGameUIPlugin)
         .add_plugin(ingame::InGamePlugin)
+        .add_plugin(landing_marker::LandingMarkerPlugin)
         .add_plugin(maze::MazePlugin)
+        .add_plugin(corn_chunks::CornChunksPlugin)
         .add_plugin(game_controller::GameControllerPlugin)
         .add_plugin(other_persons::OtherPersonsPlugin)
+        .add_plugin(particles::ParticlesPlugin)
         .add_plugin(shaders::ShadersPlugin)
+        .add_plugin(spatial_grid::SpatialGridPlugin)
+        .add_plugin(stats::StatsPlugin)
         .add_plugin(title_screen::TitlePlugin)
+        .add_plugin(trample::TramplePlugin)
         .add_plugin(player::PlayerPlugin)
+        .add_plugin(rounds::RoundsPlugin)
+        .add_plugin(scoring::ScoringPlugin)
         .add_plugin(ui::text_size::TextSizePlugin)
         .add_plugin(wasm::WasmPlugin)
         .add_system(window_settings)
@@ -108,7 +142,11 @@ pub enum AppState {
     Splash,
     LevelOver,
     ResetInGame,
+    ExtraPoint,
     Loading,
+    Editor,
+    HighScores,
+    Results,
 }
 
 pub fn cleanup<T: Component>(mut commands: Commands, entities: Query<Entity, With<T>>) {
@@ -160,7 +198,9 @@ fn debug(
     }
 
     if keys.just_pressed(KeyCode::F) {
-        football_launch_event_writer.send(football::LaunchFootballEvent);
+        football_launch_event_writer.send(football::LaunchFootballEvent::new(
+            football::FootballKind::Regular,
+        ));
     }
 
     if keys.just_pressed(KeyCode::E) {
@@ -1,62 +1,322 @@
 use crate::{
-    assets::GameAssets,
-    collision,
-    component_adder::{AnimationLink, ComponentAdder},
-    game_state, ingame, AppState,
+    assets::GameAssets, collision, component_adder::ComponentAdder, enemy, game_state, ingame,
+    maze_file, player, rounds, AppState,
 };
-use bevy::gltf::Gltf;
 use bevy::prelude::*;
 use rand::{random, Rng};
+use serde::Deserialize;
 
 #[derive(Component)]
 pub struct MazeMarker {
     pub spawned: bool,
     pub aabb: collision::WorldAabb,
+    pub kind: CornKind,
 }
 
 #[derive(Component)]
 pub struct CornStalk {
     pub is_harvested: bool,
-    pub animation_set: bool,
     pub random: f32,
+    pub kind: CornKind,
 }
 
+/// The different things a maze can be made of.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CornKind {
+    Tall,
+    // blocks movement but can be seen over
+    Short,
+    // slows movement down but doesn't block anything
+    Sparse,
+    // blocks everything and the combine can't get rid of it
+    HayBale,
+}
+
+impl CornKind {
+    pub fn blocks_movement(&self) -> bool {
+        *self != CornKind::Sparse
+    }
+
+    pub fn blocks_sight(&self) -> bool {
+        *self == CornKind::Tall || *self == CornKind::HayBale
+    }
+
+    pub fn is_harvestable(&self) -> bool {
+        *self != CornKind::HayBale
+    }
+
+    /// Whether the player can shove through it, see trample.rs.
+    pub fn is_trampleable(&self) -> bool {
+        *self == CornKind::Tall || *self == CornKind::Short
+    }
+
+    /// Maze model nodes pick their kind by name, e.g. "maze_short.003".
+    pub fn from_name(name: &str) -> Self {
+        if name.contains("hay") {
+            CornKind::HayBale
+        } else if name.contains("short") {
+            CornKind::Short
+        } else if name.contains("sparse") {
+            CornKind::Sparse
+        } else {
+            CornKind::Tall
+        }
+    }
+
+    /// The character used for this kind in maze files.
+    pub fn to_char(self) -> char {
+        match self {
+            CornKind::Tall => '#',
+            CornKind::Short => '-',
+            CornKind::Sparse => ':',
+            CornKind::HayBale => 'H',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(CornKind::Tall),
+            '-' => Some(CornKind::Short),
+            ':' => Some(CornKind::Sparse),
+            'H' => Some(CornKind::HayBale),
+            _ => None,
+        }
+    }
+}
+
+pub const SPARSE_CORN_SLOWDOWN: f32 = 0.6;
+
 #[derive(Component)]
 pub struct ShrinkCorn {
     pub shrink_time: f32,
     pub direction: Vec3,
 }
 
+/// Harvested corn that's waiting to sprout or still growing back.
+#[derive(Component)]
+pub struct RegrowingCorn {
+    pub time_left: f32,
+    pub stage: usize,
+}
+
+/// Controls whether harvested corn grows back during a round and how quickly.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct RegrowthSettings {
+    pub enabled: bool,
+    pub delay: f32,
+    pub stage_time: f32,
+    pub stages: usize,
+}
+
+impl Default for RegrowthSettings {
+    fn default() -> Self {
+        RegrowthSettings {
+            enabled: false,
+            delay: 20.0,
+            stage_time: 5.0,
+            stages: 3,
+        }
+    }
+}
+
+pub const CORN_THICKNESS: f32 = 0.8;
+
+fn corn_aabb(translation: Vec3) -> collision::WorldAabb {
+    collision::WorldAabb {
+        min: Vec3::new(
+            translation.x - CORN_THICKNESS,
+            0.0,
+            translation.z - CORN_THICKNESS,
+        ),
+        max: Vec3::new(
+            translation.x + CORN_THICKNESS,
+            0.0,
+            translation.z + CORN_THICKNESS,
+        ),
+    }
+}
+
+/// Where a round's maze comes from.
+#[derive(Deserialize, Clone)]
+pub enum MazeSource {
+    Model(String),
+    File(String),
+    Generated {
+        corridor_width: f32,
+        wall_width: f32,
+    },
+    // the maze being test played from the editor
+    #[serde(skip)]
+    Editor,
+}
+
+impl MazeSource {
+    pub fn for_round(game_state: &game_state::GameState, round: &rounds::RoundDefinition) -> Self {
+        if game_state.test_maze.is_some() {
+            MazeSource::Editor
+        } else {
+            round.maze.clone()
+        }
+    }
+}
+
+/// Spots the current maze asks enemies and combines to start from, if it has any.
+#[derive(Default, Clone)]
+pub struct MazeHints {
+    pub enemy_spawns: Vec<Vec3>,
+    pub combine_rows: Vec<f32>,
+}
+
+/// A grid of corn blocks. The edges are the world positions of the block boundaries, rows
+/// run along x and columns along z.
+pub struct MazeLayout {
+    row_edges: Vec<f32>,
+    column_edges: Vec<f32>,
+    cells: Vec<Option<CornKind>>,
+}
+
+impl MazeLayout {
+    pub fn new(row_edges: Vec<f32>, column_edges: Vec<f32>) -> Self {
+        let blocks = row_edges.len().saturating_sub(1) * column_edges.len().saturating_sub(1);
+        MazeLayout {
+            row_edges,
+            column_edges,
+            cells: vec![None; blocks],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.row_edges.len().saturating_sub(1)
+    }
+
+    pub fn columns(&self) -> usize {
+        self.column_edges.len().saturating_sub(1)
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> Option<CornKind> {
+        self.cells[(row * self.columns()) + column]
+    }
+
+    pub fn set_cell(&mut self, row: usize, column: usize, kind: Option<CornKind>) {
+        let columns = self.columns();
+        self.cells[(row * columns) + column] = kind;
+    }
+
+    /// Whether the block stops anyone from walking through it.
+    pub fn is_solid(&self, row: usize, column: usize) -> bool {
+        self.cell(row, column)
+            .is_some_and(|kind| kind.blocks_movement())
+    }
+
+    pub fn set_solid(&mut self, row: usize, column: usize, solid: bool) {
+        self.set_cell(row, column, if solid { Some(CornKind::Tall) } else { None });
+    }
+
+    /// Returns the corn as world space rectangles, merging runs of the same kind of block
+    /// along z so there are no seams between the stalks.
+    pub fn to_aabbs(&self) -> Vec<(collision::WorldAabb, CornKind)> {
+        let mut aabbs = vec![];
+        for row in 0..self.rows() {
+            let mut column = 0;
+            while column < self.columns() {
+                let kind = match self.cell(row, column) {
+                    Some(kind) => kind,
+                    None => {
+                        column += 1;
+                        continue;
+                    }
+                };
+
+                let start = column;
+                while column < self.columns() && self.cell(row, column) == Some(kind) {
+                    column += 1;
+                }
+
+                aabbs.push((
+                    collision::WorldAabb {
+                        min: Vec3::new(self.row_edges[row], 0.0, self.column_edges[start]),
+                        max: Vec3::new(self.row_edges[row + 1], 0.0, self.column_edges[column]),
+                    },
+                    kind,
+                ));
+            }
+        }
+
+        aabbs
+    }
+}
+
+/// Spawns a maze plane for each corn rectangle so spawn_corn fills them in like the ones
+/// found in maze models.
+pub fn spawn_maze_markers<T: Component + Clone>(
+    commands: &mut Commands,
+    layout: &MazeLayout,
+    cleanup_marker: T,
+) {
+    for (aabb, kind) in layout.to_aabbs() {
+        commands
+            .spawn_bundle(SpatialBundle {
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(MazeMarker {
+                spawned: false,
+                aabb,
+                kind,
+            })
+            .insert(cleanup_marker.clone())
+            .insert(Name::new("MazePlane"));
+    }
+}
+
 pub struct MazePlugin;
 impl Plugin for MazePlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(animate_corn)
+        app.init_resource::<MazeHints>()
+            .add_asset::<maze_file::MazeFile>()
+            .init_asset_loader::<maze_file::MazeFileLoader>()
             .add_system(spawn_corn)
-            .add_system_set(SystemSet::on_update(AppState::InGame).with_system(shrink_corn));
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(shrink_corn)
+                    .with_system(regrow_corn),
+            );
     }
 }
 
 fn shrink_corn(
     mut commands: Commands,
-    mut corns: Query<(Entity, &mut Transform, &mut ShrinkCorn, &AnimationLink)>,
-    mut animations: Query<&mut AnimationPlayer>,
+    mut corns: Query<(Entity, &mut Transform, &mut ShrinkCorn)>,
+    game_state: Res<game_state::GameState>,
+    rounds: Res<rounds::Rounds>,
     time: Res<Time>,
 ) {
-    for (entity, mut transform, mut shrink_corn, animation_link) in &mut corns {
+    let regrowth = rounds
+        .get(game_state.current_round)
+        .map(|round| round.regrowth.clone())
+        .unwrap_or_default();
+
+    for (entity, mut transform, mut shrink_corn) in &mut corns {
         shrink_corn.shrink_time -= time.delta_seconds();
         shrink_corn.shrink_time.clamp(0.0, 10.0);
 
         if shrink_corn.shrink_time <= 0.0 {
-            transform.scale.x = 0.1;
-            transform.scale.y = 0.1;
-            if let Some(animation_entity) = animation_link.entity {
-                if let Ok(mut animation) = animations.get_mut(animation_entity) {
-                    animation.pause();
-                    commands.entity(entity).despawn_recursive();
-                }
+            if regrowth.enabled {
+                // keep the stalk around, hidden, so it can sprout again later
+                commands
+                    .entity(entity)
+                    .remove::<ShrinkCorn>()
+                    .insert(Visibility { is_visible: false })
+                    .insert(RegrowingCorn {
+                        time_left: regrowth.delay,
+                        stage: 0,
+                    });
+            } else {
+                commands.entity(entity).despawn_recursive();
             }
         } else {
-            transform.scale.y -= 0.1;
+            transform.scale.y = (transform.scale.y - 0.1).max(0.1);
             transform
                 .rotation
                 .lerp(Quat::from_axis_angle(Vec3::X, 0.0), time.delta_seconds());
@@ -64,25 +324,67 @@ fn shrink_corn(
     }
 }
 
-fn animate_corn(
-    mut corns: Query<(&mut CornStalk, &AnimationLink)>,
-    mut animations: Query<&mut AnimationPlayer>,
-    game_assets: ResMut<GameAssets>,
+fn regrow_corn(
+    mut commands: Commands,
+    mut corns: Query<(
+        Entity,
+        &mut CornStalk,
+        &mut Transform,
+        &mut RegrowingCorn,
+        &mut Visibility,
+    )>,
+    actors: Query<
+        &Transform,
+        (
+            Or<(With<player::Player>, With<enemy::Enemy>)>,
+            Without<CornStalk>,
+        ),
+    >,
+    game_state: Res<game_state::GameState>,
+    rounds: Res<rounds::Rounds>,
+    time: Res<Time>,
 ) {
-    for (mut corn_stalk, animation_link) in &mut corns {
-        if let Some(animation_entity) = animation_link.entity {
-            if let Ok(mut animation) = animations.get_mut(animation_entity) {
-                if !corn_stalk.animation_set {
-                    animation.play(game_assets.corn_sway.clone_weak()).repeat();
-                    animation.set_speed(corn_stalk.random);
-                    animation.set_elapsed(corn_stalk.random);
-                    corn_stalk.animation_set = true;
-                }
+    let regrowth = rounds
+        .get(game_state.current_round)
+        .map(|round| round.regrowth.clone())
+        .unwrap_or_default();
 
-                if animation.is_paused() {
-                    animation.resume();
-                }
-            }
+    for (entity, mut corn, mut transform, mut regrowing, mut visibility) in &mut corns {
+        regrowing.time_left -= time.delta_seconds();
+        if regrowing.time_left > 0.0 {
+            continue;
+        }
+
+        regrowing.stage += 1;
+        if regrowing.stage < regrowth.stages {
+            // young corn, doesn't block anything yet
+            visibility.is_visible = true;
+            transform.scale = Vec3::new(1.0, regrowing.stage as f32 / regrowth.stages as f32, 1.0);
+            regrowing.time_left = regrowth.stage_time;
+            continue;
+        }
+
+        // don't grow into someone standing in the cell, they'd get stuck
+        let aabb = corn_aabb(transform.translation);
+        let is_occupied = actors.iter().any(|actor| aabb.contains(actor.translation));
+        if is_occupied {
+            regrowing.stage -= 1;
+            regrowing.time_left = 0.5;
+            continue;
+        }
+
+        // fully grown, hand it back to its corn chunk
+        corn.is_harvested = false;
+        transform.scale = Vec3::ONE;
+        transform.rotation = Quat::IDENTITY;
+        commands
+            .entity(entity)
+            .remove::<RegrowingCorn>()
+            .remove::<Handle<Mesh>>();
+        if corn.kind.blocks_movement() {
+            commands
+                .entity(entity)
+                .insert(collision::Collidable { aabb });
         }
     }
 }
@@ -90,61 +392,58 @@ fn animate_corn(
 fn spawn_corn(
     mut commands: Commands,
     mut maze_planes: Query<(&mut MazeMarker, &mut Visibility)>,
-    assets_gltf: Res<Assets<Gltf>>,
-    game_assets: Res<GameAssets>,
     game_state: Res<game_state::GameState>,
+    game_assets: Res<GameAssets>,
     mut component_adder: ResMut<ComponentAdder>,
 ) {
     let maze_thickness = if game_state.graphics_high { 0.8 } else { 1.5 };
-    let corn_height = 80.0;
-    let corn_thickness = 0.8;
-
-    if let Some(gltf) = assets_gltf.get(&game_assets.corn_stalk.clone()) {
-        let mut rng = rand::thread_rng();
-        for (mut maze_plane, mut visibility) in &mut maze_planes {
-            if maze_plane.spawned {
-                continue;
-            }
 
-            let rows = ((maze_plane.aabb.max.x - maze_plane.aabb.min.x) / maze_thickness) as usize;
-            let columns =
-                ((maze_plane.aabb.max.z - maze_plane.aabb.min.z) / maze_thickness) as usize;
-
-            for row in 0..rows {
-                for column in 0..columns {
-                    let x = maze_plane.aabb.min.x + ((row as f32 + 0.5) * maze_thickness);
-                    let z = maze_plane.aabb.min.z + ((column as f32 + 0.5) * maze_thickness);
-                    commands
-                        .spawn_bundle(SceneBundle {
-                            scene: gltf.scenes[0].clone(),
-                            transform: {
-                                let mut t = Transform::from_xyz(x, 0.0, z);
-                                //                          t.scale.y = corn_height;
-                                //                          t.scale.x = corn_thickness;
-                                //                          t.scale.z = corn_thickness;
-                                t
-                            },
-                            ..default()
-                        })
-                        .insert(collision::Collidable {
-                            aabb: collision::WorldAabb {
-                                min: Vec3::new(x - corn_thickness, 0.0, z - corn_thickness),
-                                max: Vec3::new(x + corn_thickness, 0.0, z + corn_thickness),
-                            },
-                        })
-                        .insert(AnimationLink { entity: None })
-                        .insert(game_state::LevelOverCleanupMarker)
-                        .insert(CornStalk {
-                            is_harvested: false,
-                            animation_set: false,
-                            random: rng.gen_range(0.2..0.5),
-                        })
-                        .insert(Name::new("Maze"));
+    let mut rng = rand::thread_rng();
+    for (mut maze_plane, mut visibility) in &mut maze_planes {
+        if maze_plane.spawned {
+            continue;
+        }
+
+        let rows = ((maze_plane.aabb.max.x - maze_plane.aabb.min.x) / maze_thickness) as usize;
+        let columns = ((maze_plane.aabb.max.z - maze_plane.aabb.min.z) / maze_thickness) as usize;
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let x = maze_plane.aabb.min.x + ((row as f32 + 0.5) * maze_thickness);
+                let z = maze_plane.aabb.min.z + ((column as f32 + 0.5) * maze_thickness);
+                let mut corn = if maze_plane.kind == CornKind::HayBale {
+                    commands.spawn_bundle(PbrBundle {
+                        mesh: game_assets.particle_mesh.clone(),
+                        material: game_assets.hay_bale_material.clone(),
+                        transform: Transform::from_xyz(x, 0.6, z).with_scale(Vec3::new(
+                            maze_thickness,
+                            1.2,
+                            maze_thickness,
+                        )),
+                        ..default()
+                    })
+                } else {
+                    // only the logical stalk, corn_chunks takes care of drawing it
+                    commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(
+                        x, 0.0, z,
+                    )))
+                };
+                if maze_plane.kind.blocks_movement() {
+                    corn.insert(collision::Collidable {
+                        aabb: corn_aabb(Vec3::new(x, 0.0, z)),
+                    });
                 }
+                corn.insert(game_state::LevelOverCleanupMarker)
+                    .insert(CornStalk {
+                        is_harvested: false,
+                        random: rng.gen_range(0.2..0.5),
+                        kind: maze_plane.kind,
+                    })
+                    .insert(Name::new("Maze"));
             }
-            visibility.is_visible = false; // hide the plane underneath the corn
-            maze_plane.spawned = true;
         }
+        visibility.is_visible = false; // hide the plane underneath the corn
+        maze_plane.spawned = true;
     }
 
     component_adder.has_linked = false;
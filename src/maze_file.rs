@@ -0,0 +1,182 @@
+use crate::maze::{CornKind, MazeHints, MazeLayout};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+
+/// A maze drawn in a text file. Each line is a strip of the field running from the left end
+/// zone to the right one, with the first line along the top sideline. `.` is open ground and
+/// the other characters are the kinds of corn, see CornKind::to_char. `E` and `C` are open
+/// ground marking where enemies like to spawn and which lines combines can start on.
+#[derive(TypeUuid, Clone)]
+#[uuid = "5f0b7c1e-3a2d-4e8b-9c61-2d4f8a9e7b13"]
+pub struct MazeFile {
+    pub grid: Vec<Vec<Option<CornKind>>>,
+    pub enemy_spawns: Vec<(usize, usize)>,
+    pub combine_starts: Vec<(usize, usize)>,
+}
+
+const ENEMY_SPAWN: char = 'E';
+const COMBINE_START: char = 'C';
+
+impl MazeFile {
+    /// An empty field with the given number of lines and columns.
+    pub fn new(lines: usize, columns: usize) -> Self {
+        MazeFile {
+            grid: vec![vec![None; columns]; lines],
+            enemy_spawns: vec![],
+            combine_starts: vec![],
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut grid: Vec<Vec<Option<CornKind>>> = vec![];
+        let mut enemy_spawns = vec![];
+        let mut combine_starts = vec![];
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(column, c)| match (c, CornKind::from_char(c)) {
+                    ('.', _) => Ok(None),
+                    (ENEMY_SPAWN, _) => {
+                        enemy_spawns.push((grid.len(), column));
+                        Ok(None)
+                    }
+                    (COMBINE_START, _) => {
+                        combine_starts.push((grid.len(), column));
+                        Ok(None)
+                    }
+                    (_, Some(kind)) => Ok(Some(kind)),
+                    _ => Err(format!("unexpected '{}' on line {}", c, line_number + 1)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if let Some(first) = grid.first() {
+                if first.len() != row.len() {
+                    return Err(format!(
+                        "line {} is {} wide but the maze is {} wide",
+                        line_number + 1,
+                        row.len(),
+                        first.len()
+                    ));
+                }
+            }
+            grid.push(row);
+        }
+
+        if grid.is_empty() {
+            return Err("maze is empty".to_string());
+        }
+
+        Ok(MazeFile {
+            grid,
+            enemy_spawns,
+            combine_starts,
+        })
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (line, cells) in self.grid.iter().enumerate() {
+            for (column, kind) in cells.iter().enumerate() {
+                text.push(match kind {
+                    Some(kind) => kind.to_char(),
+                    None if self.enemy_spawns.contains(&(line, column)) => ENEMY_SPAWN,
+                    None if self.combine_starts.contains(&(line, column)) => COMBINE_START,
+                    None => '.',
+                });
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// The middle of a cell once the grid is stretched over min and max (x, z).
+    pub fn cell_center(&self, line: usize, column: usize, min: Vec2, max: Vec2) -> Vec3 {
+        let cell_size = (max - min) / Vec2::new(self.grid.len() as f32, self.grid[0].len() as f32);
+        Vec3::new(
+            max.x - ((line as f32 + 0.5) * cell_size.x),
+            0.0,
+            min.y + ((column as f32 + 0.5) * cell_size.y),
+        )
+    }
+
+    pub fn hints(&self, min: Vec2, max: Vec2) -> MazeHints {
+        let mut combine_rows = self
+            .combine_starts
+            .iter()
+            .map(|(line, _)| *line)
+            .collect::<Vec<_>>();
+        combine_rows.sort_unstable();
+        combine_rows.dedup();
+
+        MazeHints {
+            enemy_spawns: self
+                .enemy_spawns
+                .iter()
+                .map(|(line, column)| self.cell_center(*line, *column, min, max))
+                .collect(),
+            combine_rows: combine_rows
+                .iter()
+                .map(|line| self.cell_center(*line, 0, min, max).x)
+                .collect(),
+        }
+    }
+
+    /// Stretches the grid over the rectangle between min and max (x, z).
+    pub fn to_layout(&self, min: Vec2, max: Vec2) -> MazeLayout {
+        let rows = self.grid.len();
+        let columns = self.grid[0].len();
+        let cell_size = (max - min) / Vec2::new(rows as f32, columns as f32);
+
+        // first line of the file is the top sideline which is the far end of x
+        let mut layout = MazeLayout::new(
+            (0..=rows)
+                .map(|i| min.x + (i as f32 * cell_size.x))
+                .collect(),
+            (0..=columns)
+                .map(|i| min.y + (i as f32 * cell_size.y))
+                .collect(),
+        );
+        for (line, cells) in self.grid.iter().enumerate() {
+            for (column, kind) in cells.iter().enumerate() {
+                layout.set_cell(rows - 1 - line, column, *kind);
+            }
+        }
+
+        layout
+    }
+}
+
+#[derive(Default)]
+pub struct MazeFileLoader;
+
+impl AssetLoader for MazeFileLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let text = std::str::from_utf8(bytes)?;
+            let maze_file = MazeFile::parse(text).map_err(|e| {
+                bevy::asset::Error::msg(format!("{:?}: {}", load_context.path(), e))
+            })?;
+            load_context.set_default_asset(LoadedAsset::new(maze_file));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["txt"]
+    }
+}
@@ -0,0 +1,207 @@
+use crate::maze::MazeLayout;
+use bevy::prelude::*;
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// narrower corridors than this can't be walked down anyway
+const MIN_CORRIDOR_WIDTH: f32 = 1.0;
+
+/// Parameters for a generated maze. The same settings always produce the same maze.
+#[derive(Copy, Clone, Debug)]
+pub struct MazeSettings {
+    pub seed: u64,
+    pub corridor_width: f32,
+    pub wall_width: f32,
+}
+
+/// Carves a maze into the rectangle between min and max (x, z) with a recursive backtracker.
+/// The maze is opened up on both z edges so there's always a path from one end zone to the
+/// other. Widths that couldn't make a maze are clamped to ones that can.
+pub fn generate(settings: MazeSettings, min: Vec2, max: Vec2) -> MazeLayout {
+    let settings = MazeSettings {
+        corridor_width: settings.corridor_width.max(MIN_CORRIDOR_WIDTH),
+        wall_width: settings.wall_width.max(0.0),
+        ..settings
+    };
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+    let pitch = settings.corridor_width + settings.wall_width;
+    let size = max - min;
+
+    let cell_rows = (((size.x - settings.wall_width) / pitch) as usize).max(1);
+    let cell_columns = (((size.y - settings.wall_width) / pitch) as usize).max(1);
+
+    // center the maze in the given area, even blocks are walls and odd blocks are cells
+    let used = Vec2::new(
+        (cell_rows as f32 * pitch) + settings.wall_width,
+        (cell_columns as f32 * pitch) + settings.wall_width,
+    );
+    let start = min + ((size - used) / 2.0);
+    let mut layout = MazeLayout::new(
+        block_edges(start.x, (cell_rows * 2) + 1, &settings),
+        block_edges(start.y, (cell_columns * 2) + 1, &settings),
+    );
+    for row in 0..layout.rows() {
+        for column in 0..layout.columns() {
+            layout.set_solid(row, column, true);
+        }
+    }
+
+    let mut visited = vec![false; cell_rows * cell_columns];
+    let first = (rng.gen_range(0..cell_rows), rng.gen_range(0..cell_columns));
+    let mut stack = vec![first];
+    visited[(first.0 * cell_columns) + first.1] = true;
+    layout.set_solid((first.0 * 2) + 1, (first.1 * 2) + 1, false);
+
+    while let Some(&(row, column)) = stack.last() {
+        let mut neighbors = vec![];
+        if row > 0 {
+            neighbors.push((row - 1, column));
+        }
+        if row + 1 < cell_rows {
+            neighbors.push((row + 1, column));
+        }
+        if column > 0 {
+            neighbors.push((row, column - 1));
+        }
+        if column + 1 < cell_columns {
+            neighbors.push((row, column + 1));
+        }
+        neighbors.retain(|(r, c)| !visited[(r * cell_columns) + c]);
+
+        match neighbors.choose(&mut rng) {
+            Some(&(next_row, next_column)) => {
+                visited[(next_row * cell_columns) + next_column] = true;
+                // knock down the wall between the two cells
+                layout.set_solid(row + next_row + 1, column + next_column + 1, false);
+                layout.set_solid((next_row * 2) + 1, (next_column * 2) + 1, false);
+                stack.push((next_row, next_column));
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    // every cell is reachable, so an opening on each end connects both end zones
+    let entrance = rng.gen_range(0..cell_rows);
+    let exit = rng.gen_range(0..cell_rows);
+    let last_column = layout.columns() - 1;
+    layout.set_solid((entrance * 2) + 1, 0, false);
+    layout.set_solid((exit * 2) + 1, last_column, false);
+
+    layout
+}
+
+// blocks alternate between wall and corridor widths
+fn block_edges(start: f32, blocks: usize, settings: &MazeSettings) -> Vec<f32> {
+    let mut edges = vec![start];
+    for index in 0..blocks {
+        let width = if index % 2 == 0 {
+            settings.wall_width
+        } else {
+            settings.corridor_width
+        };
+        edges.push(edges[index] + width);
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn settings(seed: u64) -> MazeSettings {
+        MazeSettings {
+            seed,
+            corridor_width: 4.5,
+            wall_width: 2.0,
+        }
+    }
+
+    fn solid_blocks(layout: &MazeLayout) -> Vec<bool> {
+        (0..layout.rows())
+            .flat_map(|row| (0..layout.columns()).map(move |column| (row, column)))
+            .map(|(row, column)| layout.is_solid(row, column))
+            .collect()
+    }
+
+    // walks the open blocks from the first column and sees if the last one is reached
+    fn goals_are_connected(layout: &MazeLayout) -> bool {
+        let (rows, columns) = (layout.rows(), layout.columns());
+        let mut seen = vec![false; rows * columns];
+        let mut queue = (0..rows)
+            .filter(|row| !layout.is_solid(*row, 0))
+            .map(|row| (row, 0))
+            .collect::<VecDeque<_>>();
+        for (row, column) in queue.iter() {
+            seen[(row * columns) + column] = true;
+        }
+
+        while let Some((row, column)) = queue.pop_front() {
+            if column == columns - 1 {
+                return true;
+            }
+            let neighbors = [
+                (row.wrapping_sub(1), column),
+                (row + 1, column),
+                (row, column.wrapping_sub(1)),
+                (row, column + 1),
+            ];
+            for (next_row, next_column) in neighbors {
+                if next_row < rows
+                    && next_column < columns
+                    && !seen[(next_row * columns) + next_column]
+                    && !layout.is_solid(next_row, next_column)
+                {
+                    seen[(next_row * columns) + next_column] = true;
+                    queue.push_back((next_row, next_column));
+                }
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_maze() {
+        let (min, max) = (Vec2::new(-20.0, -35.0), Vec2::new(20.0, 35.0));
+        let first = generate(settings(7), min, max);
+        let second = generate(settings(7), min, max);
+        assert_eq!(solid_blocks(&first), solid_blocks(&second));
+        assert_ne!(
+            solid_blocks(&first),
+            solid_blocks(&generate(settings(8), min, max))
+        );
+    }
+
+    #[test]
+    fn bad_widths_still_make_a_maze() {
+        let (min, max) = (Vec2::new(-20.0, -35.0), Vec2::new(20.0, 35.0));
+        for (corridor_width, wall_width) in [
+            (0.0, 0.0),
+            (0.0, 2.0),
+            (-3.0, -1.0),
+            (f32::NAN, 2.0),
+            (4.5, 500.0),
+        ] {
+            let settings = MazeSettings {
+                seed: 0,
+                corridor_width,
+                wall_width,
+            };
+            let layout = generate(settings, min, max);
+            assert!(layout.rows() > 0 && layout.columns() > 0);
+            assert!(goals_are_connected(&layout));
+        }
+    }
+
+    #[test]
+    fn there_is_always_a_way_through() {
+        let (min, max) = (Vec2::new(-20.0, -35.0), Vec2::new(20.0, 35.0));
+        for seed in 0..50 {
+            let layout = generate(settings(seed), min, max);
+            assert!(goals_are_connected(&layout), "seed {}", seed);
+        }
+    }
+}
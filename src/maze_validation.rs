@@ -0,0 +1,587 @@
+use crate::{
+    collision::{self, WorldAabb},
+    field::FieldLayout,
+    maze, maze_file, maze_generator, rounds, spatial_grid,
+};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Checks mazes without starting the game, i.e. `football_combine validate-maze mazes/round1.txt`.
+/// With no mazes given it checks every maze in the round definitions.
+pub fn run(args: &[String]) -> i32 {
+    let checks = if args.is_empty() {
+        match round_mazes() {
+            Ok(checks) => checks,
+            Err(e) => {
+                eprintln!("{}", e);
+                return 1;
+            }
+        }
+    } else {
+        args.iter()
+            .map(|path| {
+                let field = FieldLayout::default();
+                (path.clone(), field, maze_from_path(path, &field))
+            })
+            .collect()
+    };
+
+    let mut failed = false;
+    for (name, field, obstacles) in checks {
+        match obstacles {
+            Ok(obstacles) => {
+                let report = validate(&obstacles, &field);
+                println!("{}\n{}", name, report);
+                failed = failed || !report.is_playable();
+            }
+            Err(e) => {
+                println!("{}\n  couldn't load maze: {}", name, e);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        1
+    } else {
+        0
+    }
+}
+
+// how many seeds to try for each generated maze
+const GENERATED_SEEDS: u64 = 10;
+
+type MazeCheck = (String, FieldLayout, Result<Vec<WorldAabb>, String>);
+
+fn round_mazes() -> Result<Vec<MazeCheck>, String> {
+    let bytes = std::fs::read(asset_path("data/rounds.ron"))
+        .map_err(|e| format!("couldn't read rounds: {}", e))?;
+    let definitions = ron::de::from_bytes::<rounds::RoundDefinitions>(&bytes)
+        .map_err(|e| format!("couldn't parse rounds: {}", e))?;
+
+    let mut checks = vec![];
+    for (round, definition) in definitions.rounds.iter().enumerate() {
+        let field = definition.field;
+        match &definition.maze {
+            maze::MazeSource::Model(path) | maze::MazeSource::File(path) => {
+                checks.push((
+                    format!("round {}: {}", round + 1, path),
+                    field,
+                    maze_from_path(path, &field),
+                ));
+            }
+            // only exists while the editor is test playing
+            maze::MazeSource::Editor => (),
+            maze::MazeSource::Generated {
+                corridor_width,
+                wall_width,
+            } => {
+                for seed in 0..GENERATED_SEEDS {
+                    let settings = maze_generator::MazeSettings {
+                        seed,
+                        corridor_width: *corridor_width,
+                        wall_width: *wall_width,
+                    };
+                    checks.push((
+                        format!("round {}: generated with seed {}", round + 1, seed),
+                        field,
+                        Ok(obstacles_from_layout(&generate(settings, &field))),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(checks)
+}
+
+fn asset_path(path: &str) -> PathBuf {
+    if Path::new(path).exists() {
+        PathBuf::from(path)
+    } else {
+        Path::new("assets").join(path)
+    }
+}
+
+fn generate(settings: maze_generator::MazeSettings, field: &FieldLayout) -> maze::MazeLayout {
+    let (min, max) = field.maze_area();
+    maze_generator::generate(settings, min, max)
+}
+
+/// Loads a maze file or model, going by the extension. Maze files get stretched over the
+/// field's maze area, models are already placed.
+pub fn maze_from_path(path: &str, field: &FieldLayout) -> Result<Vec<WorldAabb>, String> {
+    let bytes = std::fs::read(asset_path(path)).map_err(|e| e.to_string())?;
+    if path.ends_with(".glb") || path.ends_with(".gltf") {
+        obstacles_from_glb(&bytes)
+    } else {
+        let text = std::str::from_utf8(&bytes).map_err(|e| e.to_string())?;
+        let (min, max) = field.maze_area();
+        let layout = maze_file::MazeFile::parse(text)?.to_layout(min, max);
+        Ok(obstacles_from_layout(&layout))
+    }
+}
+
+/// Grows a box on x and z by the margin.
+fn grow(aabb: WorldAabb, margin: f32) -> WorldAabb {
+    let margin = Vec3::new(margin, 0.0, margin);
+    WorldAabb {
+        min: aabb.min.min(aabb.max) - margin,
+        max: aabb.min.max(aabb.max) + margin,
+    }
+}
+
+/// The area taken up by a block of corn once spawn_corn fills it in. Stalks stick out past
+/// the block a little, and actors bump into it from their radius away, so this grows it to
+/// match.
+fn corn_block(aabb: WorldAabb) -> WorldAabb {
+    grow(aabb, (maze::CORN_THICKNESS / 2.0) + collision::ACTOR_RADIUS)
+}
+
+pub fn obstacles_from_layout(layout: &maze::MazeLayout) -> Vec<WorldAabb> {
+    layout
+        .to_aabbs()
+        .into_iter()
+        .filter(|(_, kind)| kind.blocks_movement())
+        .map(|(aabb, _)| corn_block(aabb))
+        .collect()
+}
+
+/// Finds the same maze and collidable nodes that component_adder does in a maze model.
+pub fn obstacles_from_glb(bytes: &[u8]) -> Result<Vec<WorldAabb>, String> {
+    let gltf = gltf::Gltf::from_slice(bytes).map_err(|e| e.to_string())?;
+    let scene = gltf
+        .default_scene()
+        .or_else(|| gltf.scenes().next())
+        .ok_or_else(|| "model has no scenes".to_string())?;
+
+    let mut obstacles = vec![];
+    let mut nodes = scene
+        .nodes()
+        .map(|node| (node, Mat4::IDENTITY))
+        .collect::<Vec<_>>();
+    while let Some((node, parent_matrix)) = nodes.pop() {
+        let matrix = parent_matrix * Mat4::from_cols_array_2d(&node.transform().matrix());
+        let name = node.name().unwrap_or_default();
+        let is_maze = name.contains("maze");
+        let blocks = if is_maze {
+            maze::CornKind::from_name(name).blocks_movement()
+        } else {
+            name.contains("collidable")
+        };
+
+        if let (true, Some(mesh)) = (blocks, node.mesh()) {
+            for primitive in mesh.primitives() {
+                let bounds = primitive.bounding_box();
+                let (min, max) = spatial_grid::world_bounds(
+                    &matrix,
+                    Vec3::from(bounds.min),
+                    Vec3::from(bounds.max),
+                );
+                let aabb = WorldAabb { min, max };
+                obstacles.push(if is_maze {
+                    corn_block(aabb)
+                } else {
+                    grow(aabb, collision::ACTOR_RADIUS)
+                });
+            }
+        }
+
+        nodes.extend(node.children().map(|child| (child, matrix)));
+    }
+
+    Ok(obstacles)
+}
+
+const CELL_SIZE: f32 = 0.5;
+
+// the cells along one axis with their middles between min and max
+fn covered_cells(min: f32, max: f32, start: f32, count: usize) -> std::ops::Range<usize> {
+    let first = (((min - start) / CELL_SIZE) - 0.5).ceil().max(0.0) as usize;
+    let last = (((max - start) / CELL_SIZE) - 0.5).floor() + 1.0;
+    first..(last.max(0.0) as usize).min(count)
+}
+
+/// The field from end to end split into small cells that are either open or blocked.
+pub struct WalkableGrid {
+    min: Vec2,
+    rows: usize,
+    columns: usize,
+    blocked: Vec<bool>,
+}
+
+impl WalkableGrid {
+    /// Blocks every cell with its middle inside one of the obstacles.
+    pub fn new(obstacles: &[WorldAabb], field: &FieldLayout) -> Self {
+        let min = Vec2::new(field.bottom_end, field.left_end);
+        let rows = ((field.top_end - field.bottom_end) / CELL_SIZE).ceil() as usize;
+        let columns = ((field.right_end - field.left_end) / CELL_SIZE).ceil() as usize;
+        let mut grid = WalkableGrid {
+            min,
+            rows,
+            columns,
+            blocked: vec![false; rows * columns],
+        };
+
+        // only visit the cells each box covers instead of checking every box for every cell
+        for aabb in obstacles {
+            for row in covered_cells(aabb.min.x, aabb.max.x, min.x, rows) {
+                for column in covered_cells(aabb.min.z, aabb.max.z, min.y, columns) {
+                    grid.blocked[(row * columns) + column] = true;
+                }
+            }
+        }
+
+        grid
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.blocked.len()
+    }
+
+    /// The middle of the cell on the field.
+    pub fn cell_center(&self, index: usize) -> Vec3 {
+        let (row, column) = (index / self.columns, index % self.columns);
+        Vec3::new(
+            self.min.x + ((row as f32 + 0.5) * CELL_SIZE),
+            0.0,
+            self.min.y + ((column as f32 + 0.5) * CELL_SIZE),
+        )
+    }
+
+    pub fn is_open(&self, index: usize) -> bool {
+        !self.blocked[index]
+    }
+
+    pub fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> {
+        let (row, column) = (index / self.columns, index % self.columns);
+        let columns = self.columns;
+        [
+            (row > 0).then(|| index - columns),
+            (row + 1 < self.rows).then(|| index + columns),
+            (column > 0).then(|| index - 1),
+            (column + 1 < self.columns).then(|| index + 1),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    pub fn cell_at(&self, position: Vec3) -> Option<usize> {
+        let row = ((position.x - self.min.x) / CELL_SIZE).floor();
+        let column = ((position.z - self.min.y) / CELL_SIZE).floor();
+        if row < 0.0 || column < 0.0 || row as usize >= self.rows || column as usize >= self.columns
+        {
+            return None;
+        }
+
+        Some((row as usize * self.columns) + column as usize)
+    }
+
+    /// The cell the position is in, or the closest one along the edge when it's off the grid.
+    pub fn nearest_cell(&self, position: Vec3) -> usize {
+        let row = ((position.x - self.min.x) / CELL_SIZE)
+            .floor()
+            .clamp(0.0, self.rows.saturating_sub(1) as f32);
+        let column = ((position.z - self.min.y) / CELL_SIZE)
+            .floor()
+            .clamp(0.0, self.columns.saturating_sub(1) as f32);
+        (row as usize * self.columns) + column as usize
+    }
+
+    /// The open cell closest to the position.
+    fn nearest_open(&self, position: Vec3) -> Option<usize> {
+        (0..self.blocked.len())
+            .filter(|index| self.is_open(*index))
+            .min_by(|a, b| {
+                let a = self.cell_center(*a);
+                let b = self.cell_center(*b);
+                a.distance_squared(position)
+                    .partial_cmp(&b.distance_squared(position))
+                    .unwrap()
+            })
+    }
+
+    /// Labels every open cell with the connected area it belongs to.
+    fn regions(&self) -> (Vec<Option<usize>>, usize) {
+        let mut labels = vec![None; self.blocked.len()];
+        let mut count = 0;
+        for start in 0..self.blocked.len() {
+            if !self.is_open(start) || labels[start].is_some() {
+                continue;
+            }
+
+            labels[start] = Some(count);
+            let mut queue = VecDeque::from([start]);
+            while let Some(index) = queue.pop_front() {
+                for neighbor in self.neighbors(index) {
+                    if self.is_open(neighbor) && labels[neighbor].is_none() {
+                        labels[neighbor] = Some(count);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            count += 1;
+        }
+
+        (labels, count)
+    }
+}
+
+/// Open ground the player can't get to from where they start.
+pub struct Pocket {
+    pub area: f32,
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+pub struct MazeReport {
+    pub reaches_left_goal: bool,
+    pub reaches_right_goal: bool,
+    pub pockets: Vec<Pocket>,
+    // how much of the random spawn area isn't blocked, if this is zero the spawn loops in
+    // enemy and football never finish
+    pub spawn_coverage: f32,
+    // how much of the random spawn area the player can actually get to
+    pub reachable_spawn_coverage: f32,
+}
+
+impl MazeReport {
+    pub fn is_playable(&self) -> bool {
+        self.reaches_left_goal
+            && self.reaches_right_goal
+            && self.spawn_coverage > 0.0
+            && self.reachable_spawn_coverage > 0.0
+    }
+}
+
+impl fmt::Display for MazeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reachable = |is_reachable| {
+            if is_reachable {
+                "reachable"
+            } else {
+                "UNREACHABLE"
+            }
+        };
+        writeln!(f, "  left goal: {}", reachable(self.reaches_left_goal))?;
+        writeln!(f, "  right goal: {}", reachable(self.reaches_right_goal))?;
+        writeln!(
+            f,
+            "  spawn area: {:.0}% open, {:.0}% reachable",
+            self.spawn_coverage * 100.0,
+            self.reachable_spawn_coverage * 100.0
+        )?;
+        writeln!(f, "  isolated pockets: {}", self.pockets.len())?;
+        for pocket in self.pockets.iter() {
+            writeln!(
+                f,
+                "    {:.1} square units from ({:.1}, {:.1}) to ({:.1}, {:.1})",
+                pocket.area, pocket.min.x, pocket.min.z, pocket.max.x, pocket.max.z
+            )?;
+        }
+        write!(f, "  {}", if self.is_playable() { "ok" } else { "FAILED" })
+    }
+}
+
+pub fn validate(obstacles: &[WorldAabb], field: &FieldLayout) -> MazeReport {
+    let grid = WalkableGrid::new(obstacles, field);
+    let (labels, region_count) = grid.regions();
+    let start_region = grid
+        .nearest_open(field.player_start())
+        .and_then(|index| labels[index]);
+
+    let mut reaches_left_goal = false;
+    let mut reaches_right_goal = false;
+    let mut pockets = (0..region_count)
+        .map(|_| Pocket {
+            area: 0.0,
+            min: Vec3::splat(f32::MAX),
+            max: Vec3::splat(f32::MIN),
+        })
+        .collect::<Vec<_>>();
+
+    let spawn_area = field.spawn_area();
+    let mut spawn_cells = 0;
+    let mut open_spawn_cells = 0;
+    let mut reachable_spawn_cells = 0;
+
+    for (index, label) in labels.iter().enumerate() {
+        let center = grid.cell_center(index);
+        let in_spawn_area = spawn_area.contains(center);
+        if in_spawn_area {
+            spawn_cells += 1;
+        }
+
+        let label = match label {
+            Some(label) => *label,
+            None => continue,
+        };
+        let is_reachable = Some(label) == start_region;
+
+        if in_spawn_area {
+            open_spawn_cells += 1;
+            if is_reachable {
+                reachable_spawn_cells += 1;
+            }
+        }
+
+        if is_reachable {
+            reaches_left_goal = reaches_left_goal || center.z <= field.left_goal;
+            reaches_right_goal = reaches_right_goal || center.z >= field.right_goal;
+        } else {
+            let pocket = &mut pockets[label];
+            pocket.area += CELL_SIZE * CELL_SIZE;
+            pocket.min = pocket.min.min(center);
+            pocket.max = pocket.max.max(center);
+        }
+    }
+
+    let coverage = |cells: usize| {
+        if spawn_cells == 0 {
+            0.0
+        } else {
+            cells as f32 / spawn_cells as f32
+        }
+    };
+
+    MazeReport {
+        reaches_left_goal,
+        reaches_right_goal,
+        pockets: pockets
+            .into_iter()
+            .filter(|pocket| pocket.area > 0.0)
+            .collect(),
+        spawn_coverage: coverage(open_spawn_cells),
+        reachable_spawn_coverage: coverage(reachable_spawn_cells),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest_path(path: &str) -> String {
+        format!("{}/assets/{}", env!("CARGO_MANIFEST_DIR"), path)
+    }
+
+    fn wall(min_x: f32, min_z: f32, max_x: f32, max_z: f32) -> WorldAabb {
+        WorldAabb {
+            min: Vec3::new(min_x, 0.0, min_z),
+            max: Vec3::new(max_x, 0.0, max_z),
+        }
+    }
+
+    #[test]
+    fn open_field_is_playable() {
+        let report = validate(&[], &FieldLayout::default());
+        assert!(report.is_playable());
+        assert!(report.pockets.is_empty());
+        assert_eq!(report.spawn_coverage, 1.0);
+    }
+
+    #[test]
+    fn wall_across_the_field_blocks_the_right_goal() {
+        let field = FieldLayout::default();
+        let report = validate(
+            &[wall(field.bottom_end - 1.0, 0.0, field.top_end + 1.0, 2.0)],
+            &field,
+        );
+        assert!(report.reaches_left_goal);
+        assert!(!report.reaches_right_goal);
+        assert!(!report.is_playable());
+        assert_eq!(report.pockets.len(), 1);
+    }
+
+    #[test]
+    fn closed_box_is_a_pocket() {
+        let report = validate(
+            &[
+                wall(-6.0, -6.0, 6.0, -5.0),
+                wall(-6.0, 5.0, 6.0, 6.0),
+                wall(-6.0, -6.0, -5.0, 6.0),
+                wall(5.0, -6.0, 6.0, 6.0),
+            ],
+            &FieldLayout::default(),
+        );
+        assert!(report.is_playable());
+        assert_eq!(report.pockets.len(), 1);
+        assert!(report.pockets[0].area > 50.0 && report.pockets[0].area < 121.0);
+    }
+
+    #[test]
+    fn blocked_spawn_area_never_spawns() {
+        let field = FieldLayout::default();
+        let spawn_area = field.spawn_area();
+        let report = validate(
+            &[WorldAabb {
+                min: spawn_area.min - Vec3::ONE,
+                max: spawn_area.max + Vec3::ONE,
+            }],
+            &field,
+        );
+        assert_eq!(report.spawn_coverage, 0.0);
+        assert!(!report.is_playable());
+    }
+
+    #[test]
+    fn maze_files_fit_smaller_fields() {
+        let field = FieldLayout {
+            left_goal: -18.0,
+            right_goal: 18.0,
+            left_end: -24.0,
+            right_end: 24.0,
+            bottom_end: -10.0,
+            top_end: 10.0,
+            ..FieldLayout::default()
+        };
+        let obstacles = maze_from_path(&manifest_path("mazes/round1.txt"), &field).unwrap();
+        assert!(obstacles.iter().all(
+            |aabb| aabb.min.z >= field.left_goal - 1.0 && aabb.max.z <= field.right_goal + 1.0
+        ));
+        let report = validate(&obstacles, &field);
+        assert!(report.is_playable(), "{}", report);
+    }
+
+    #[test]
+    fn round_mazes_are_playable() {
+        let bytes = std::fs::read(manifest_path("data/rounds.ron")).unwrap();
+        let definitions = ron::de::from_bytes::<rounds::RoundDefinitions>(&bytes).unwrap();
+        for definition in definitions.rounds.iter() {
+            let field = definition.field;
+            let mazes = match &definition.maze {
+                maze::MazeSource::Model(path) | maze::MazeSource::File(path) => {
+                    let path = manifest_path(path);
+                    // models aren't always checked in alongside the code
+                    if !Path::new(&path).exists() {
+                        continue;
+                    }
+                    vec![(path.clone(), maze_from_path(&path, &field).unwrap())]
+                }
+                maze::MazeSource::Editor => continue,
+                maze::MazeSource::Generated {
+                    corridor_width,
+                    wall_width,
+                } => (0..GENERATED_SEEDS)
+                    .map(|seed| {
+                        let settings = maze_generator::MazeSettings {
+                            seed,
+                            corridor_width: *corridor_width,
+                            wall_width: *wall_width,
+                        };
+                        (
+                            format!("seed {}", seed),
+                            obstacles_from_layout(&generate(settings, &field)),
+                        )
+                    })
+                    .collect(),
+            };
+
+            for (name, obstacles) in mazes {
+                let report = validate(&obstacles, &field);
+                assert!(report.is_playable(), "{}\n{}", name, report);
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     asset_loading, assets::GameAssets, audio::GameAudio, cleanup, game_controller, game_state,
-    menus, title_screen::MenuAction, ui::text_size, AppState,
+    menus, stats, title_screen::MenuAction, ui::text_size, AppState,
 };
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
@@ -57,6 +57,8 @@ pub struct OptionState {
     graphics: usize,
     shadows_on: usize,
     game_version: usize,
+    combine_warning: usize,
+    landing_marker: usize,
 }
 
 impl OptionState {
@@ -65,6 +67,8 @@ impl OptionState {
             graphics: 0,
             shadows_on: 0,
             game_version: 0,
+            combine_warning: 0,
+            landing_marker: 0,
         }
     }
 }
@@ -82,7 +86,7 @@ fn setup(
     mut current_option: ResMut<CurrentOption>,
     text_scaler: text_size::TextScaler,
 ) {
-    current_option.0 = 3;
+    current_option.0 = 5;
 
     commands
         .spawn_bundle(Camera3dBundle {
@@ -138,7 +142,7 @@ fn setup(
             parent
                 .spawn_bundle(NodeBundle {
                     style: Style {
-                        size: Size::new(Val::Percent(100.0), Val::Percent(15.0)),
+                        size: Size::new(Val::Percent(100.0), Val::Percent(11.0)),
                         position_type: PositionType::Relative,
                         align_items: AlignItems::FlexEnd,
                         ..Default::default()
@@ -194,7 +198,7 @@ fn setup(
             parent
                 .spawn_bundle(NodeBundle {
                     style: Style {
-                        size: Size::new(Val::Percent(100.0), Val::Percent(15.0)),
+                        size: Size::new(Val::Percent(100.0), Val::Percent(11.0)),
                         position_type: PositionType::Relative,
                         align_items: AlignItems::FlexEnd,
                         ..Default::default()
@@ -251,7 +255,7 @@ fn setup(
             parent
                 .spawn_bundle(NodeBundle {
                     style: Style {
-                        size: Size::new(Val::Percent(100.0), Val::Percent(15.0)),
+                        size: Size::new(Val::Percent(100.0), Val::Percent(11.0)),
                         position_type: PositionType::Relative,
                         align_items: AlignItems::FlexEnd,
                         ..Default::default()
@@ -305,6 +309,120 @@ fn setup(
                         });
                 });
 
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(11.0)),
+                        position_type: PositionType::Relative,
+                        align_items: AlignItems::FlexEnd,
+                        ..Default::default()
+                    },
+                    color: Color::NONE.into(),
+                    ..Default::default()
+                })
+                .insert(OptionRow { row: 3 })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(50.0), Val::Percent(100.0)),
+                                position_type: PositionType::Relative,
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::FlexEnd,
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            add_label(
+                                parent,
+                                game_assets.font.clone(),
+                                text_scaler.scale(menus::DEFAULT_FONT_SIZE),
+                                "Warning    :",
+                                vec![OptionRow { row: 3 }],
+                            );
+                        });
+
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(50.0), Val::Percent(100.0)),
+                                position_type: PositionType::Relative,
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::FlexEnd,
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            add_option(
+                                parent,
+                                game_assets.font.clone(),
+                                text_scaler.scale(menus::SCORE_FONT_SIZE),
+                                vec![OptionRow { row: 3 }],
+                            );
+                        });
+                });
+
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(11.0)),
+                        position_type: PositionType::Relative,
+                        align_items: AlignItems::FlexEnd,
+                        ..Default::default()
+                    },
+                    color: Color::NONE.into(),
+                    ..Default::default()
+                })
+                .insert(OptionRow { row: 4 })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(50.0), Val::Percent(100.0)),
+                                position_type: PositionType::Relative,
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::FlexEnd,
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            add_label(
+                                parent,
+                                game_assets.font.clone(),
+                                text_scaler.scale(menus::DEFAULT_FONT_SIZE),
+                                "Marker     :",
+                                vec![OptionRow { row: 4 }],
+                            );
+                        });
+
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(50.0), Val::Percent(100.0)),
+                                position_type: PositionType::Relative,
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::FlexEnd,
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            add_option(
+                                parent,
+                                game_assets.font.clone(),
+                                text_scaler.scale(menus::SCORE_FONT_SIZE),
+                                vec![OptionRow { row: 4 }],
+                            );
+                        });
+                });
+
             parent
                 .spawn_bundle(NodeBundle {
                     style: Style {
@@ -313,7 +431,7 @@ fn setup(
                         margin: UiRect {
                             left: Val::Auto,
                             right: Val::Auto,
-                            top: Val::Percent(20.0),
+                            top: Val::Percent(10.0),
                             ..Default::default()
                         },
                         justify_content: JustifyContent::Center,
@@ -323,14 +441,14 @@ fn setup(
                     color: Color::NONE.into(),
                     ..Default::default()
                 })
-                .insert(OptionRow { row: 3 })
+                .insert(OptionRow { row: 5 })
                 .with_children(|parent| {
                     add_button(
                         parent,
                         game_assets.font.clone(),
                         text_scaler.scale(menus::SCORE_FONT_SIZE),
                         "Start Game",
-                        vec![OptionRow { row: 3 }],
+                        vec![OptionRow { row: 5 }],
                     );
                 });
         });
@@ -573,7 +691,7 @@ fn update_menu_buttons(
     mut option_change_event_writer: EventWriter<OptionChangeEvent>,
 ) {
     let action_state = action_state.single();
-    let max_options = 3;
+    let max_options = 5;
 
     if action_state.just_pressed(MenuAction::Up) {
         audio.play_sfx(&game_assets.blip);
@@ -644,6 +762,7 @@ fn handle_option_changes(
     mut options: ResMut<OptionState>,
     mut game_assets: ResMut<GameAssets>,
     mut game_state: ResMut<game_state::GameState>,
+    mut run_stats: ResMut<stats::RunStats>,
     mut assets_handler: asset_loading::AssetsHandler,
     mut audio: GameAudio,
 ) {
@@ -711,12 +830,61 @@ fn handle_option_changes(
                 };
             }
             3 => {
+                let min = 0;
+                let max = 1;
+                match option_change.action {
+                    OptionChange::Increase => {
+                        options.combine_warning = if options.combine_warning == max {
+                            min
+                        } else {
+                            max
+                        };
+                        audio.play_sfx(&game_assets.blip);
+                    }
+                    OptionChange::Decrease => {
+                        options.combine_warning = if options.combine_warning == max {
+                            min
+                        } else {
+                            max
+                        };
+                        audio.play_sfx(&game_assets.blip);
+                    }
+                    _ => (),
+                };
+            }
+            4 => {
+                let min = 0;
+                let max = 1;
+                match option_change.action {
+                    OptionChange::Increase => {
+                        options.landing_marker = if options.landing_marker == max {
+                            min
+                        } else {
+                            max
+                        };
+                        audio.play_sfx(&game_assets.blip);
+                    }
+                    OptionChange::Decrease => {
+                        options.landing_marker = if options.landing_marker == max {
+                            min
+                        } else {
+                            max
+                        };
+                        audio.play_sfx(&game_assets.blip);
+                    }
+                    _ => (),
+                };
+            }
+            5 => {
                 if let OptionChange::Select = option_change.action {
                     *game_state = game_state::GameState::initialize(
                         options.graphics == 0,
                         options.shadows_on == 0,
                         options.game_version == 0,
+                        options.combine_warning == 0,
+                        options.landing_marker == 0,
                     );
+                    *run_stats = stats::RunStats::default();
 
                     audio.play_sfx(&game_assets.blip);
                     assets_handler.load(AppState::InGame, &mut game_assets, &mut game_state);
@@ -752,5 +920,19 @@ fn display_current_options(
                 _ => " Jam  ".to_string(),
             };
         }
+
+        if option_row.row == 3 {
+            option_text.sections[0].value = match option_state.combine_warning {
+                0 => "  On  ".to_string(),
+                _ => "  Off ".to_string(),
+            };
+        }
+
+        if option_row.row == 4 {
+            option_text.sections[0].value = match option_state.landing_marker {
+                0 => "Always".to_string(),
+                _ => "In View".to_string(),
+            };
+        }
     }
 }
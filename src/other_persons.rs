@@ -1,6 +1,6 @@
 use crate::{
     assets::GameAssets, collision, component_adder::AnimationLink, direction, game_controller,
-    game_state, maze, player, AppState, ZeroSignum, BOTTOM_END, LEFT_GOAL, RIGHT_GOAL, TOP_END,
+    game_state, maze, player, AppState, ZeroSignum,
 };
 use bevy::prelude::*;
 use bevy::render::primitives::Aabb;
@@ -0,0 +1,239 @@
+use crate::{assets::GameAssets, game_state, ingame, AppState};
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+pub struct ParticlesPlugin;
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnParticlesEvent>().add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(handle_spawn_particles_event)
+                .with_system(update_particles),
+        );
+    }
+}
+
+// total particles alive at once, depends on the graphics option
+const HIGH_GRAPHICS_BUDGET: usize = 800;
+const LOW_GRAPHICS_BUDGET: usize = 150;
+
+pub const CONFETTI_COLORS: [Color; 5] = [
+    Color::RED,
+    Color::WHITE,
+    Color::BLUE,
+    Color::YELLOW,
+    Color::GREEN,
+];
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum ParticleKind {
+    Chaff,
+    Dust,
+    Smoke,
+    Confetti,
+}
+
+pub struct SpawnParticlesEvent {
+    pub kind: ParticleKind,
+    pub position: Vec3,
+    pub direction: Vec3,
+}
+
+impl SpawnParticlesEvent {
+    pub fn new(kind: ParticleKind, position: Vec3) -> Self {
+        SpawnParticlesEvent {
+            kind,
+            position,
+            direction: Vec3::Y,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Particle {
+    velocity: Vec3,
+    spin: Vec3,
+    scale: Vec3,
+    gravity: f32,
+    friction: f32,
+    lifetime: f32,
+    total_lifetime: f32,
+}
+
+fn handle_spawn_particles_event(
+    mut commands: Commands,
+    mut spawn_particles_event_reader: EventReader<SpawnParticlesEvent>,
+    particles: Query<Entity, With<Particle>>,
+    game_assets: Res<GameAssets>,
+    game_state: Res<game_state::GameState>,
+) {
+    let budget = if game_state.graphics_high {
+        HIGH_GRAPHICS_BUDGET
+    } else {
+        LOW_GRAPHICS_BUDGET
+    };
+    let mut alive = particles.iter().len();
+    let mut rng = rand::thread_rng();
+
+    for event in spawn_particles_event_reader.iter() {
+        let count = match (event.kind, game_state.graphics_high) {
+            (ParticleKind::Chaff, true) => 4,
+            (ParticleKind::Chaff, false) => 1,
+            (ParticleKind::Dust, true) => 12,
+            (ParticleKind::Dust, false) => 4,
+            (ParticleKind::Smoke, true) => 3,
+            (ParticleKind::Smoke, false) => 1,
+            (ParticleKind::Confetti, true) => 120,
+            (ParticleKind::Confetti, false) => 30,
+        };
+        let count = count.min(budget.saturating_sub(alive));
+        alive += count;
+
+        for _ in 0..count {
+            let (material, particle) = match event.kind {
+                ParticleKind::Chaff => {
+                    let spread = Vec3::new(
+                        rng.gen_range(-1.0..1.0),
+                        rng.gen_range(0.5..1.5),
+                        rng.gen_range(-1.0..1.0),
+                    );
+                    (
+                        game_assets.chaff_material.clone(),
+                        Particle {
+                            velocity: (event.direction.normalize_or_zero() + spread)
+                                * rng.gen_range(4.0..9.0),
+                            spin: Vec3::new(
+                                rng.gen_range(-8.0..8.0),
+                                rng.gen_range(-8.0..8.0),
+                                rng.gen_range(-8.0..8.0),
+                            ),
+                            scale: Vec3::new(0.25, 0.03, 0.1),
+                            gravity: 9.0,
+                            friction: 0.2,
+                            lifetime: 0.0,
+                            total_lifetime: rng.gen_range(1.0..2.0),
+                        },
+                    )
+                }
+                ParticleKind::Dust => {
+                    let angle = rng.gen_range(0.0..TAU);
+                    (
+                        game_assets.dust_material.clone(),
+                        Particle {
+                            velocity: Vec3::new(
+                                angle.cos() * rng.gen_range(2.0..5.0),
+                                rng.gen_range(0.5..2.0),
+                                angle.sin() * rng.gen_range(2.0..5.0),
+                            ),
+                            spin: Vec3::new(0.0, rng.gen_range(-2.0..2.0), 0.0),
+                            scale: Vec3::splat(rng.gen_range(0.3..0.6)),
+                            gravity: 0.5,
+                            friction: 0.05,
+                            lifetime: 0.0,
+                            total_lifetime: rng.gen_range(0.4..0.8),
+                        },
+                    )
+                }
+                ParticleKind::Smoke => (
+                    game_assets.smoke_material.clone(),
+                    Particle {
+                        velocity: Vec3::new(
+                            rng.gen_range(-0.5..0.5),
+                            rng.gen_range(2.0..4.0),
+                            rng.gen_range(-0.5..0.5),
+                        ),
+                        spin: Vec3::new(0.0, rng.gen_range(-1.0..1.0), 0.0),
+                        scale: Vec3::splat(rng.gen_range(0.8..1.5)),
+                        gravity: -1.0,
+                        friction: 0.5,
+                        lifetime: 0.0,
+                        total_lifetime: rng.gen_range(1.5..2.5),
+                    },
+                ),
+                ParticleKind::Confetti => {
+                    let color = rng.gen_range(0..game_assets.confetti_materials.len().max(1));
+                    (
+                        game_assets
+                            .confetti_materials
+                            .get(color)
+                            .cloned()
+                            .unwrap_or_default(),
+                        Particle {
+                            velocity: Vec3::new(
+                                rng.gen_range(-6.0..6.0),
+                                rng.gen_range(8.0..16.0),
+                                rng.gen_range(-6.0..6.0),
+                            ),
+                            spin: Vec3::new(
+                                rng.gen_range(-10.0..10.0),
+                                rng.gen_range(-10.0..10.0),
+                                rng.gen_range(-10.0..10.0),
+                            ),
+                            scale: Vec3::new(0.2, 0.02, 0.12),
+                            gravity: 6.0,
+                            friction: 0.3,
+                            lifetime: 0.0,
+                            total_lifetime: rng.gen_range(2.0..3.5),
+                        },
+                    )
+                }
+            };
+
+            commands
+                .spawn_bundle(PbrBundle {
+                    mesh: game_assets.particle_mesh.clone(),
+                    material,
+                    transform: {
+                        let mut t = Transform::from_translation(event.position);
+                        t.scale = particle.scale;
+                        t
+                    },
+                    ..Default::default()
+                })
+                .insert(particle)
+                .insert(NotShadowCaster)
+                .insert(NotShadowReceiver)
+                .insert(ingame::CleanupMarker);
+        }
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut particle, mut transform) in &mut particles {
+        particle.lifetime += time.delta_seconds();
+        if particle.lifetime >= particle.total_lifetime {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let friction = particle.friction;
+        let gravity = particle.gravity;
+        particle.velocity *= friction.powf(time.delta_seconds());
+        particle.velocity.y -= gravity * time.delta_seconds();
+        transform.translation += particle.velocity * time.delta_seconds();
+
+        if transform.translation.y < 0.0 {
+            // settle on the ground
+            transform.translation.y = 0.0;
+            particle.velocity = Vec3::new(
+                particle.velocity.x * 0.5,
+                -particle.velocity.y * 0.2,
+                particle.velocity.z * 0.5,
+            );
+            particle.spin *= 0.5;
+        }
+
+        let spin = particle.spin * time.delta_seconds();
+        transform.rotate(Quat::from_euler(EulerRot::XYZ, spin.x, spin.y, spin.z));
+
+        // shrink away during the last bit of its life
+        let remaining = 1.0 - (particle.lifetime / particle.total_lifetime);
+        transform.scale = particle.scale * (remaining / 0.3).min(1.0);
+    }
+}
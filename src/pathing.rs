@@ -0,0 +1,143 @@
+use crate::maze_validation::WalkableGrid;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// How many steps every open cell is from the nearest cell that counts as arrived, so
+/// anything standing in the grid can find its way there by always heading downhill.
+pub struct Route {
+    grid: WalkableGrid,
+    steps: Vec<Option<usize>>,
+    // the closest open cell to every cell, for getting back out of the corn
+    nearest_open: Vec<Option<usize>>,
+}
+
+impl Route {
+    pub fn new(grid: WalkableGrid, is_destination: impl Fn(Vec3) -> bool) -> Self {
+        let mut steps = vec![None; grid.cell_count()];
+        let mut queue = VecDeque::new();
+        for (index, cell_steps) in steps.iter_mut().enumerate() {
+            let center = grid.cell_center(index);
+            if grid.is_open(index) && is_destination(center) {
+                *cell_steps = Some(0);
+                queue.push_back(index);
+            }
+        }
+
+        while let Some(index) = queue.pop_front() {
+            let next = steps[index].map(|steps| steps + 1);
+            for neighbor in grid.neighbors(index) {
+                if grid.is_open(neighbor) && steps[neighbor].is_none() {
+                    steps[neighbor] = next;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        // spread out from the open cells once instead of searching every time
+        let mut nearest_open = (0..grid.cell_count())
+            .map(|index| grid.is_open(index).then_some(index))
+            .collect::<Vec<_>>();
+        let mut queue = (0..nearest_open.len())
+            .filter(|index| nearest_open[*index].is_some())
+            .collect::<VecDeque<_>>();
+        while let Some(index) = queue.pop_front() {
+            for neighbor in grid.neighbors(index) {
+                if nearest_open[neighbor].is_none() {
+                    nearest_open[neighbor] = nearest_open[index];
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        Route {
+            grid,
+            steps,
+            nearest_open,
+        }
+    }
+
+    /// The middle of the next cell to head for, or None when already there or there's no way
+    /// through.
+    pub fn next_waypoint(&self, position: Vec3) -> Option<Vec3> {
+        let grid = &self.grid;
+        let current = grid
+            .cell_at(position)
+            .filter(|index| self.steps[*index].is_some())
+            .or_else(|| self.nearest_open[grid.nearest_cell(position)])?;
+        let current_steps = self.steps[current]?;
+        if current_steps == 0 {
+            return None;
+        }
+
+        // standing somewhere blocked, so get back onto the route first
+        if grid.cell_at(position) != Some(current) {
+            return Some(grid.cell_center(current));
+        }
+
+        grid.neighbors(current)
+            .filter_map(|neighbor| self.steps[neighbor].map(|steps| (neighbor, steps)))
+            .min_by_key(|(_, steps)| *steps)
+            .map(|(neighbor, _)| grid.cell_center(neighbor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{collision::WorldAabb, field::FieldLayout};
+
+    fn wall(min_x: f32, min_z: f32, max_x: f32, max_z: f32) -> WorldAabb {
+        WorldAabb {
+            min: Vec3::new(min_x, 0.0, min_z),
+            max: Vec3::new(max_x, 0.0, max_z),
+        }
+    }
+
+    #[test]
+    fn route_goes_around_walls() {
+        let field = FieldLayout::default();
+        // a wall across the field with a gap along the top sideline
+        let grid = WalkableGrid::new(
+            &[wall(field.bottom_end - 1.0, 0.0, field.top_end - 4.0, 2.0)],
+            &field,
+        );
+        let route = Route::new(grid, |center| center.z >= field.right_goal);
+
+        let mut position = Vec3::new(0.0, 0.0, -5.0);
+        let mut reached_gap = false;
+        for _ in 0..1000 {
+            match route.next_waypoint(position) {
+                Some(waypoint) => position = waypoint,
+                None => break,
+            }
+            reached_gap = reached_gap || position.x > field.top_end - 4.0;
+        }
+        assert!(reached_gap);
+        assert!(position.z >= field.right_goal);
+        assert_eq!(route.next_waypoint(position), None);
+    }
+
+    #[test]
+    fn route_with_no_way_through_gives_up() {
+        let field = FieldLayout::default();
+        let grid = WalkableGrid::new(
+            &[wall(field.bottom_end - 1.0, 0.0, field.top_end + 1.0, 2.0)],
+            &field,
+        );
+        let route = Route::new(grid, |center| center.z >= field.right_goal);
+        assert_eq!(route.next_waypoint(Vec3::new(0.0, 0.0, -5.0)), None);
+        assert!(route.next_waypoint(Vec3::new(0.0, 0.0, 10.0)).is_some());
+    }
+
+    #[test]
+    fn route_gets_back_out_of_the_corn() {
+        let field = FieldLayout::default();
+        let grid = WalkableGrid::new(&[wall(-2.0, -2.0, 2.0, 2.0)], &field);
+        let route = Route::new(grid, |center| center.z >= field.right_goal);
+
+        let waypoint = route.next_waypoint(Vec3::new(0.0, 0.0, 1.9)).unwrap();
+        assert!(waypoint.z > 2.0 && waypoint.z < 3.0);
+        let off_field = Vec3::new(0.0, 0.0, field.left_end - 10.0);
+        assert!(route.next_waypoint(off_field).unwrap().z > field.left_end);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
-    assets::GameAssets, audio::GameAudio, billboard::Billboard, collision,
-    component_adder::AnimationLink, cutscene, direction, football, game_controller, game_state,
-    ingame, AppState, ZeroSignum, LEFT_GOAL, RIGHT_GOAL,
+    assets::GameAssets, audio::GameAudio, billboard::Billboard, collision, combine,
+    component_adder::AnimationLink, cutscene, direction, field, football, game_controller,
+    game_state, ingame, particles, trample, AppState, ZeroSignum,
 };
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
@@ -54,20 +54,29 @@ pub fn handle_player_blade_event(
 pub fn check_for_touchdown(
     mut players: Query<(Entity, &Transform, &mut Player)>,
     game_state: Res<game_state::GameState>,
+    field: Res<field::FieldLayout>,
     mut touchdown_event_writer: EventWriter<game_state::TouchdownEvent>,
     mut carried_footballs: Query<(&football::CarriedFootball, &mut Visibility, &Parent)>,
     game_assets: Res<GameAssets>,
     mut audio: GameAudio,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
 ) {
     for (player_entity, player_transform, mut player) in &mut players {
         if player.has_football
-            && ((game_state.touchdown_on_leftside && player_transform.translation.z <= LEFT_GOAL)
+            && ((game_state.touchdown_on_leftside
+                && player_transform.translation.z <= field.left_goal)
                 || (!game_state.touchdown_on_leftside
-                    && player_transform.translation.z >= RIGHT_GOAL))
+                    && player_transform.translation.z >= field.right_goal))
         {
             player.has_football = false;
-            touchdown_event_writer.send(game_state::TouchdownEvent);
+            touchdown_event_writer.send(game_state::TouchdownEvent {
+                football: player.carried_football,
+            });
             audio.play_sfx(&game_assets.touch_down);
+            spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                particles::ParticleKind::Confetti,
+                player_transform.translation,
+            ));
             println!("Sending touch");
             for (_, mut visibility, parent) in &mut carried_footballs {
                 if player_entity == parent.get() {
@@ -80,7 +89,13 @@ pub fn check_for_touchdown(
 
 pub fn move_player(
     time: Res<Time>,
-    mut players: Query<(Entity, &mut Transform, &mut Player, &AnimationLink)>,
+    mut players: Query<(
+        Entity,
+        &mut Transform,
+        &mut Player,
+        &AnimationLink,
+        &collision::ActorBody,
+    )>,
     mut animations: Query<&mut AnimationPlayer>,
     mut player_move_event_reader: EventReader<PlayerMoveEvent>,
     collidables: collision::Collidables,
@@ -88,13 +103,16 @@ pub fn move_player(
     game_assets: ResMut<GameAssets>,
     mut audio: GameAudio,
     mut cutscene_state: ResMut<cutscene::CutsceneState>,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    wheel_ruts: Query<&combine::WheelRut>,
+    mut trample_event_writer: EventWriter<trample::TrampleEvent>,
 ) {
     let mut move_events = HashMap::new();
     for move_event in player_move_event_reader.iter() {
         move_events.entry(move_event.entity).or_insert(move_event);
     }
 
-    for (entity, mut transform, mut player, animation_link) in players.iter_mut() {
+    for (entity, mut transform, mut player, animation_link, body) in players.iter_mut() {
         if !player.is_tackled && game_state.attached_enemies >= 3 {
             if let Some(animation_entity) = animation_link.entity {
                 let mut animation = animations.get_mut(animation_entity).unwrap();
@@ -104,6 +122,10 @@ pub fn move_player(
             }
             player.is_tackled = true;
             audio.play_sfx(&game_assets.tackle_sound);
+            spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                particles::ParticleKind::Dust,
+                transform.translation,
+            ));
             player.dead_cooldown = 1.2;
         }
         if player.is_dead || player.is_tackled {
@@ -123,7 +145,14 @@ pub fn move_player(
             continue;
         }
 
-        let speed: f32 = player.speed;
+        let speed: f32 = if wheel_ruts
+            .iter()
+            .any(|r| r.contains(&transform.translation))
+        {
+            player.speed * combine::WHEEL_RUT_SLOWDOWN
+        } else {
+            player.speed
+        };
         let rotation_speed: f32 = player.rotation_speed;
         let friction: f32 = player.friction;
 
@@ -142,11 +171,23 @@ pub fn move_player(
 
         player.velocity = player.velocity.clamp_length_max(speed);
         let mut new_translation = transform.translation + (player.velocity * time.delta_seconds());
+
+        // shoving through corn is slow going, the stalks only give way after a while
+        if move_events.contains_key(&entity) && collidables.is_in_trampleable_corn(&new_translation)
+        {
+            trample_event_writer.send(trample::TrampleEvent {
+                position: new_translation,
+                direction: (new_translation - transform.translation).normalize_or_zero(),
+                amount: time.delta_seconds(),
+            });
+            new_translation = transform.translation
+                + ((new_translation - transform.translation) * trample::TRAMPLE_SLOWDOWN);
+        }
         collidables.fit_in(
             &transform.translation,
             &mut new_translation,
             &mut player.velocity,
-            &time,
+            body.radius,
         );
 
         let angle = (-(new_translation.z - transform.translation.z))
@@ -239,6 +280,7 @@ pub struct Player {
     pub random: f32,
     pub current_animation: Handle<AnimationClip>,
     pub has_football: bool,
+    pub carried_football: football::FootballKind,
 }
 
 impl Player {
@@ -256,6 +298,7 @@ impl Player {
             random: rng.gen_range(0.5..1.0),
             current_animation: Handle::<AnimationClip>::default(),
             has_football: false,
+            carried_football: football::FootballKind::Regular,
         }
     }
 }
@@ -263,6 +306,7 @@ impl Player {
 #[derive(Bundle)]
 pub struct PlayerBundle {
     player: Player,
+    body: collision::ActorBody,
     #[bundle]
     input_manager: InputManagerBundle<PlayerAction>,
 }
@@ -271,6 +315,10 @@ impl PlayerBundle {
     pub fn new() -> Self {
         PlayerBundle {
             player: Player::new(),
+            body: collision::ActorBody {
+                radius: collision::ACTOR_RADIUS,
+                mass: 1.0,
+            },
             input_manager: InputManagerBundle {
                 input_map: PlayerBundle::default_input_map(),
                 action_state: ActionState::default(),
@@ -0,0 +1,133 @@
+use crate::{bonus, combine, cutscene, enemy, extra_point, field, maze};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+pub struct RoundsPlugin;
+impl Plugin for RoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Rounds::default())
+            .add_asset::<RoundDefinitions>()
+            .init_asset_loader::<RoundDefinitionsLoader>();
+    }
+}
+
+/// Everything that changes from one round to the next, loaded from assets/data/rounds.ron.
+#[derive(Deserialize, Clone)]
+pub struct RoundDefinition {
+    pub maze: maze::MazeSource,
+    pub lighting: LightingPreset,
+    pub enemies: Vec<EnemySpawn>,
+    pub combine_count: usize,
+    pub combine_speed: f32,
+    pub music: String,
+    pub intro: cutscene::Cutscene,
+    pub outro: cutscene::Cutscene,
+    #[serde(default)]
+    pub breakdowns: combine::BreakdownSettings,
+    #[serde(default)]
+    pub regrowth: maze::RegrowthSettings,
+    #[serde(default)]
+    pub field: field::FieldLayout,
+    #[serde(default)]
+    pub bonuses: bonus::BonusSettings,
+    #[serde(default)]
+    pub extra_points: extra_point::ExtraPointSettings,
+}
+
+impl Default for RoundDefinition {
+    fn default() -> Self {
+        RoundDefinition {
+            maze: maze::MazeSource::Model("models/maze.glb".to_string()),
+            lighting: LightingPreset::Day,
+            enemies: vec![EnemySpawn {
+                kind: enemy::EnemyKind::Linebacker,
+                count: 3,
+            }],
+            combine_count: 1,
+            combine_speed: 1.0,
+            music: "audio/combine.ogg".to_string(),
+            intro: cutscene::Cutscene::Intro,
+            outro: cutscene::Cutscene::RoundOneOver,
+            breakdowns: combine::BreakdownSettings::default(),
+            regrowth: maze::RegrowthSettings::default(),
+            field: field::FieldLayout::default(),
+            bonuses: bonus::BonusSettings::default(),
+            extra_points: extra_point::ExtraPointSettings::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub enum LightingPreset {
+    Day,
+    Sunset,
+    Night,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct EnemySpawn {
+    pub kind: enemy::EnemyKind,
+    pub count: usize,
+}
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "a3c9e2d4-6f1b-4b7a-8e05-9d2c7f4b1e68"]
+pub struct RoundDefinitions {
+    pub rounds: Vec<RoundDefinition>,
+}
+
+/// The rounds of the current game. Copied out of the loaded asset when the title screen
+/// starts so systems don't need to go through the asset storage.
+pub struct Rounds {
+    definitions: Vec<RoundDefinition>,
+}
+
+impl Rounds {
+    pub fn set(&mut self, definitions: &RoundDefinitions) {
+        if !definitions.rounds.is_empty() {
+            self.definitions = definitions.rounds.clone();
+        }
+    }
+
+    pub fn get(&self, round: usize) -> Option<&RoundDefinition> {
+        self.definitions.get(round)
+    }
+
+    pub fn total(&self) -> usize {
+        self.definitions.len()
+    }
+}
+
+impl Default for Rounds {
+    fn default() -> Self {
+        Rounds {
+            definitions: vec![RoundDefinition::default()],
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RoundDefinitionsLoader;
+
+impl AssetLoader for RoundDefinitionsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let round_definitions = ron::de::from_bytes::<RoundDefinitions>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(round_definitions));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
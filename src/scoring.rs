@@ -0,0 +1,355 @@
+use crate::{
+    assets::GameAssets, enemy, field, football, game_state, ingame, ingame_ui, menus, player,
+    ui::text_size, AppState,
+};
+use bevy::prelude::*;
+
+/// Works out what each touchdown is worth from how the drive went and keeps a combo going
+/// for touchdowns scored back to back without getting tackled.
+pub struct ScoringPlugin;
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Drive::default())
+            .insert_resource(Combo::default())
+            .add_system_set(SystemSet::on_exit(AppState::ResetInGame).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(score_touchdowns.after(player::check_for_touchdown))
+                    .with_system(track_drive.after(score_touchdowns))
+                    .with_system(update_breakdown_popup)
+                    .with_system(update_combo_meter),
+            );
+    }
+}
+
+const YARD_POINTS: f32 = 1.0;
+// for every defender hanging on when the player crosses the goal line
+const ATTACHED_POINTS: usize = 25;
+// drives quicker than this get a bonus for every second left over
+const QUICK_TIME: f32 = 10.0;
+const QUICK_POINTS: f32 = 5.0;
+// for every enemy the combine threw while the player had the ball
+const LURED_POINTS: usize = 50;
+// each touchdown in a row adds this to the multiplier
+const COMBO_STEP: f32 = 0.25;
+const MAX_MULTIPLIER: f32 = 3.0;
+// how long the breakdown stays up after a touchdown
+const POPUP_TIME: f32 = 3.0;
+
+/// How the current trip with the ball has gone so far.
+#[derive(Default, Clone)]
+pub struct Drive {
+    pub is_active: bool,
+    pub yards: f32,
+    pub time: f32,
+    pub lured: usize,
+    last_position: Vec3,
+}
+
+#[derive(Default)]
+pub struct Combo {
+    // touchdowns scored in a row without getting tackled
+    pub streak: usize,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> f32 {
+        (1.0 + (COMBO_STEP * self.streak as f32)).min(MAX_MULTIPLIER)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TouchdownScore {
+    pub lines: Vec<(String, usize)>,
+    pub multiplier: f32,
+    pub total: usize,
+}
+
+/// What a touchdown with this ball is worth. Two-point tries are always worth the same.
+pub fn score_touchdown(
+    football: football::FootballKind,
+    drive: &Drive,
+    attached_enemies: usize,
+    combo: &Combo,
+) -> TouchdownScore {
+    let mut lines = vec![("TOUCHDOWN".to_string(), football.points())];
+    if football == football::FootballKind::Conversion {
+        return TouchdownScore {
+            total: football.points(),
+            lines,
+            multiplier: 1.0,
+        };
+    }
+
+    let yards = drive.yards.round() as usize;
+    if yards > 0 {
+        lines.push((
+            format!("RUN {} YDS", yards),
+            (yards as f32 * YARD_POINTS) as usize,
+        ));
+    }
+    if attached_enemies > 0 {
+        lines.push((
+            format!("DRAGGED {}", attached_enemies),
+            attached_enemies * ATTACHED_POINTS,
+        ));
+    }
+    if drive.time < QUICK_TIME {
+        lines.push((
+            format!("QUICK {:.1}S", drive.time),
+            ((QUICK_TIME - drive.time) * QUICK_POINTS) as usize,
+        ));
+    }
+    if drive.lured > 0 {
+        lines.push((
+            format!("COMBINED {}", drive.lured),
+            drive.lured * LURED_POINTS,
+        ));
+    }
+
+    let multiplier = combo.multiplier();
+    let subtotal = lines.iter().map(|(_, points)| points).sum::<usize>();
+    TouchdownScore {
+        lines,
+        multiplier,
+        total: (subtotal as f32 * multiplier).round() as usize,
+    }
+}
+
+#[derive(Component)]
+struct BreakdownPopup {
+    time_left: f32,
+}
+
+#[derive(Component)]
+struct ComboMeter;
+
+fn setup(
+    mut commands: Commands,
+    mut drive: ResMut<Drive>,
+    mut combo: ResMut<Combo>,
+    game_assets: Res<GameAssets>,
+    text_scaler: text_size::TextScaler,
+) {
+    *drive = Drive::default();
+    *combo = Combo::default();
+
+    let style = TextStyle {
+        font: game_assets.font.clone(),
+        font_size: text_scaler.scale(menus::DEFAULT_FONT_SIZE * 0.6),
+        color: Color::WHITE,
+    };
+    commands
+        .spawn_bundle(
+            TextBundle::from_section("", style.clone()).with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Percent(25.0),
+                    right: Val::Percent(3.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        )
+        .insert(BreakdownPopup { time_left: 0.0 })
+        .insert(ingame::CleanupMarker)
+        .insert(ingame_ui::CleanupMarker);
+
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    color: Color::rgb(1.0, 0.8, 0.2),
+                    ..style
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Percent(2.0),
+                    right: Val::Percent(3.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        )
+        .insert(ComboMeter)
+        .insert(ingame::CleanupMarker)
+        .insert(ingame_ui::CleanupMarker);
+}
+
+fn score_touchdowns(
+    mut touchdown_event_reader: EventReader<game_state::TouchdownEvent>,
+    mut game_state: ResMut<game_state::GameState>,
+    mut drive: ResMut<Drive>,
+    mut combo: ResMut<Combo>,
+    mut popups: Query<(&mut BreakdownPopup, &mut Text)>,
+) {
+    for event in touchdown_event_reader.iter() {
+        let score = score_touchdown(event.football, &drive, game_state.attached_enemies, &combo);
+        game_state.score += score.total;
+        if event.football != football::FootballKind::Conversion {
+            combo.streak += 1;
+        }
+        *drive = Drive::default();
+
+        let mut breakdown = score
+            .lines
+            .iter()
+            .map(|(label, points)| format!("{:<12}{:>5}\n", label, points))
+            .collect::<String>();
+        if score.multiplier > 1.0 {
+            breakdown += &format!("{:<12}{:>5}\n", "COMBO", format!("x{}", score.multiplier));
+        }
+        breakdown += &format!("{:<12}{:>5}", "TOTAL", score.total);
+
+        for (mut popup, mut text) in &mut popups {
+            popup.time_left = POPUP_TIME;
+            text.sections[0].value = breakdown.clone();
+        }
+    }
+}
+
+fn track_drive(
+    mut drive: ResMut<Drive>,
+    mut combo: ResMut<Combo>,
+    players: Query<(&player::Player, &Transform)>,
+    field: Res<field::FieldLayout>,
+    mut enemy_blade_event_reader: EventReader<enemy::EnemyBladeEvent>,
+    time: Res<Time>,
+) {
+    let lured = enemy_blade_event_reader.iter().count();
+    for (player, transform) in &players {
+        if player.is_tackled && combo.streak > 0 {
+            combo.streak = 0;
+        }
+
+        if !player.has_football {
+            drive.is_active = false;
+            continue;
+        }
+
+        let position = Vec3::new(transform.translation.x, 0.0, transform.translation.z);
+        if !drive.is_active {
+            *drive = Drive {
+                is_active: true,
+                last_position: position,
+                ..default()
+            };
+        }
+
+        drive.yards += field.to_yards(position.distance(drive.last_position));
+        drive.last_position = position;
+        drive.time += time.delta_seconds();
+        drive.lured += lured;
+    }
+}
+
+fn update_breakdown_popup(
+    mut popups: Query<(&mut BreakdownPopup, &mut Visibility)>,
+    time: Res<Time>,
+) {
+    for (mut popup, mut visibility) in &mut popups {
+        popup.time_left -= time.delta_seconds();
+        visibility.is_visible = popup.time_left > 0.0;
+    }
+}
+
+fn update_combo_meter(combo: Res<Combo>, mut meters: Query<&mut Text, With<ComboMeter>>) {
+    if !combo.is_changed() {
+        return;
+    }
+
+    let value = if combo.streak == 0 {
+        String::new()
+    } else {
+        // one block for every touchdown in the streak until the multiplier tops out
+        let steps = ((MAX_MULTIPLIER - 1.0) / COMBO_STEP) as usize;
+        let filled = combo.streak.min(steps);
+        format!(
+            "COMBO x{} [{}{}]",
+            combo.multiplier(),
+            "#".repeat(filled),
+            "-".repeat(steps - filled)
+        )
+    };
+    for mut text in &mut meters {
+        text.sections[0].value = value.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use football::FootballKind;
+
+    fn drive(yards: f32, time: f32, lured: usize) -> Drive {
+        Drive {
+            is_active: true,
+            yards,
+            time,
+            lured,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn slow_short_drive_is_just_the_touchdown() {
+        let score = score_touchdown(
+            FootballKind::Regular,
+            &drive(0.0, QUICK_TIME + 5.0, 0),
+            0,
+            &Combo::default(),
+        );
+        assert_eq!(score.total, FootballKind::Regular.points());
+        assert_eq!(score.lines.len(), 1);
+    }
+
+    #[test]
+    fn every_factor_adds_up() {
+        let score = score_touchdown(
+            FootballKind::Regular,
+            &drive(40.0, QUICK_TIME - 2.0, 1),
+            2,
+            &Combo::default(),
+        );
+        let expected = FootballKind::Regular.points()
+            + 40
+            + (2 * ATTACHED_POINTS)
+            + (2.0 * QUICK_POINTS) as usize
+            + LURED_POINTS;
+        assert_eq!(score.total, expected);
+        assert_eq!(score.lines.len(), 5);
+    }
+
+    #[test]
+    fn combo_multiplies_the_whole_drive() {
+        let combo = Combo { streak: 2 };
+        let score = score_touchdown(
+            FootballKind::Regular,
+            &drive(20.0, QUICK_TIME, 0),
+            0,
+            &combo,
+        );
+        assert_eq!(score.multiplier, 1.5);
+        assert_eq!(score.total, 180);
+    }
+
+    #[test]
+    fn combo_tops_out() {
+        assert_eq!(Combo { streak: 100 }.multiplier(), MAX_MULTIPLIER);
+    }
+
+    #[test]
+    fn conversions_are_flat() {
+        let score = score_touchdown(
+            FootballKind::Conversion,
+            &drive(30.0, 1.0, 2),
+            3,
+            &Combo { streak: 4 },
+        );
+        assert_eq!(score.total, football::CONVERSION_POINTS);
+        assert_eq!(score.multiplier, 1.0);
+    }
+}
@@ -19,6 +19,7 @@ impl Plugin for ShadersPlugin {
         app.add_plugin(MaterialPlugin::<FresnelMaterial>::default())
             .add_plugin(Material2dPlugin::<PostProcessingMaterial>::default())
             .add_plugin(MaterialPlugin::<TextureMaterial>::default())
+            .add_plugin(MaterialPlugin::<CornMaterial>::default())
             .add_system(pass_time_to_shader);
     }
 }
@@ -65,6 +66,29 @@ impl Material for TextureMaterial {
     }
 }
 
+/// Used for the merged corn chunks. Sways each stalk in the vertex shader using the phase
+/// stored in the red channel of its vertex colors.
+#[derive(AsBindGroup, Debug, Clone, TypeUuid)]
+#[uuid = "917f64fe-6844-4822-8926-e0ed374294c8"]
+pub struct CornMaterial {
+    #[uniform(0)]
+    pub color: Color,
+    #[uniform(1)]
+    pub time: f32,
+    #[uniform(2)]
+    pub sway_strength: f32,
+}
+
+impl Material for CornMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/corn.wgsl".into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        "shaders/corn.wgsl".into()
+    }
+}
+
 #[derive(AsBindGroup, TypeUuid, Clone)]
 #[uuid = "bc2f08eb-a0fb-43f1-a908-54871ea597d5"]
 pub struct PostProcessingMaterial {
@@ -86,6 +110,8 @@ fn pass_time_to_shader(
     mut fresnel_materials: ResMut<Assets<FresnelMaterial>>,
     textures: Query<&Handle<TextureMaterial>>,
     mut texture_materials: ResMut<Assets<TextureMaterial>>,
+    corns: Query<&Handle<CornMaterial>>,
+    mut corn_materials: ResMut<Assets<CornMaterial>>,
 ) {
     for fresnel_handle in &fresnels {
         if let Some(material) = fresnel_materials.get_mut(fresnel_handle) {
@@ -98,4 +124,10 @@ fn pass_time_to_shader(
             material.time = time.time_since_startup().as_secs_f32();
         }
     }
+
+    for corn_handle in &corns {
+        if let Some(material) = corn_materials.get_mut(corn_handle) {
+            material.time = time.time_since_startup().as_secs_f32();
+        }
+    }
 }
@@ -0,0 +1,152 @@
+use crate::{collision, maze::CornStalk};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Keeps corn stalks and static collidables bucketed by where they are on the field so
+/// systems can look up what's near a spot instead of scanning everything.
+pub struct SpatialGridPlugin;
+impl Plugin for SpatialGridPlugin {
+    fn build(&self, app: &mut App) {
+        // removed components are only reported until the end of the frame, so this has to run
+        // after Update instead of before it
+        app.insert_resource(SpatialGrid::default())
+            .add_system_to_stage(CoreStage::PostUpdate, update_spatial_grid);
+    }
+}
+
+const CELL_SIZE: f32 = 4.0;
+
+#[derive(Default)]
+pub struct SpatialGrid {
+    corn: HashMap<IVec2, Vec<(Entity, Vec3)>>,
+    corn_cells: HashMap<Entity, IVec2>,
+    collidables: HashMap<IVec2, Vec<Entity>>,
+    collidable_cells: HashMap<Entity, Vec<IVec2>>,
+}
+
+fn cell(position: Vec3) -> IVec2 {
+    IVec2::new(
+        (position.x / CELL_SIZE).floor() as i32,
+        (position.z / CELL_SIZE).floor() as i32,
+    )
+}
+
+fn cells_between(min: Vec3, max: Vec3) -> impl Iterator<Item = IVec2> {
+    let (min, max) = (cell(min.min(max)), cell(min.max(max)));
+    (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |z| IVec2::new(x, z)))
+}
+
+/// The world space box around a local space one, for looking up what's near a rotated hitbox.
+pub fn world_bounds(matrix: &Mat4, min: Vec3, max: Vec3) -> (Vec3, Vec3) {
+    [
+        Vec3::new(min.x, min.y, min.z),
+        Vec3::new(min.x, min.y, max.z),
+        Vec3::new(max.x, min.y, min.z),
+        Vec3::new(max.x, min.y, max.z),
+    ]
+    .iter()
+    .map(|corner| matrix.transform_point3(*corner))
+    .fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), corner| (min.min(corner), max.max(corner)),
+    )
+}
+
+impl SpatialGrid {
+    /// Unharvested corn that might be inside the given box.
+    pub fn corn_between(&self, min: Vec3, max: Vec3) -> impl Iterator<Item = Entity> + '_ {
+        cells_between(min, max)
+            .filter_map(|c| self.corn.get(&c))
+            .flat_map(|entities| entities.iter().map(|(entity, _)| *entity))
+    }
+
+    /// Unharvested corn within distance of the position along both x and z.
+    pub fn corn_near(&self, position: Vec3, distance: f32) -> impl Iterator<Item = Entity> + '_ {
+        let offset = Vec3::new(distance, 0.0, distance);
+        cells_between(position - offset, position + offset)
+            .filter_map(|c| self.corn.get(&c))
+            .flat_map(|entities| entities.iter())
+            .filter(move |(_, corn)| {
+                (corn.x - position.x).abs() <= distance && (corn.z - position.z).abs() <= distance
+            })
+            .map(|(entity, _)| *entity)
+    }
+
+    /// Static collidables that might contain the given position.
+    pub fn collidables_at(&self, position: Vec3) -> impl Iterator<Item = Entity> + '_ {
+        self.collidables
+            .get(&cell(position))
+            .into_iter()
+            .flat_map(|entities| entities.iter().copied())
+    }
+
+    /// Static collidables that might overlap the given box, each one only once.
+    pub fn collidables_between(&self, min: Vec3, max: Vec3) -> Vec<Entity> {
+        let mut entities = cells_between(min, max)
+            .filter_map(|c| self.collidables.get(&c))
+            .flat_map(|entities| entities.iter().copied())
+            .collect::<Vec<_>>();
+        entities.sort_unstable();
+        entities.dedup();
+        entities
+    }
+
+    fn add_corn(&mut self, entity: Entity, position: Vec3) {
+        let key = cell(position);
+        self.corn.entry(key).or_default().push((entity, position));
+        self.corn_cells.insert(entity, key);
+    }
+
+    fn remove_corn(&mut self, entity: Entity) {
+        if let Some(key) = self.corn_cells.remove(&entity) {
+            if let Some(entities) = self.corn.get_mut(&key) {
+                entities.retain(|(e, _)| *e != entity);
+            }
+        }
+    }
+
+    fn add_collidable(&mut self, entity: Entity, aabb: &collision::WorldAabb) {
+        let keys = cells_between(aabb.min, aabb.max).collect::<Vec<_>>();
+        for key in keys.iter() {
+            self.collidables.entry(*key).or_default().push(entity);
+        }
+        self.collidable_cells.insert(entity, keys);
+    }
+
+    fn remove_collidable(&mut self, entity: Entity) {
+        if let Some(keys) = self.collidable_cells.remove(&entity) {
+            for key in keys {
+                if let Some(entities) = self.collidables.get_mut(&key) {
+                    entities.retain(|e| *e != entity);
+                }
+            }
+        }
+    }
+}
+
+fn update_spatial_grid(
+    mut spatial_grid: ResMut<SpatialGrid>,
+    collidables: Query<(Entity, &collision::Collidable), Added<collision::Collidable>>,
+    corns: Query<(Entity, &CornStalk, &Transform), Changed<CornStalk>>,
+    removed_collidables: RemovedComponents<collision::Collidable>,
+    removed_corns: RemovedComponents<CornStalk>,
+) {
+    for entity in removed_collidables.iter() {
+        spatial_grid.remove_collidable(entity);
+    }
+    for entity in removed_corns.iter() {
+        spatial_grid.remove_corn(entity);
+    }
+
+    for (entity, collidable) in &collidables {
+        spatial_grid.remove_collidable(entity);
+        spatial_grid.add_collidable(entity, &collidable.aabb);
+    }
+
+    for (entity, corn, transform) in &corns {
+        spatial_grid.remove_corn(entity);
+        if !corn.is_harvested {
+            spatial_grid.add_corn(entity, transform.translation);
+        }
+    }
+}
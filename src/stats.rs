@@ -0,0 +1,296 @@
+use crate::{
+    asset_loading, assets::GameAssets, audio::GameAudio, cleanup, enemy, field, football,
+    game_controller, game_state, menus, player, title_screen::MenuAction, ui::text_size, AppState,
+};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+/// Counts up what happened during the round and the whole run, then shows it on a results
+/// screen once the round's outro is over.
+pub struct StatsPlugin;
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RunStats::default())
+            .add_event::<StatEvent>()
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(record_events)
+                    .with_system(track_player),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Results)
+                    .with_system(setup)
+                    .with_system(game_controller::clear_presses),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Results)
+                    .with_system(leave_results.after(handle_controllers))
+                    .with_system(
+                        handle_controllers.after(game_controller::store_controller_inputs),
+                    ),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Results)
+                    .with_system(cleanup::<CleanupMarker>)
+                    .with_system(game_controller::clear_presses),
+            );
+    }
+}
+
+// anything further than this in one frame was a teleport, not a run
+const MAX_STEP: f32 = 2.0;
+
+#[derive(Component)]
+struct CleanupMarker;
+
+/// Things worth counting that the rest of the game doesn't already send events for.
+pub enum StatEvent {
+    Pickup,
+    DiveDodged,
+    Attached,
+    CornHarvested,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub touchdowns: usize,
+    pub pickups: usize,
+    pub yards: f32,
+    pub dives_dodged: usize,
+    pub times_attached: usize,
+    pub enemies_bladed: usize,
+    // only while the player has the ball
+    pub corn_harvested: usize,
+    pub time_alive: f32,
+}
+
+impl Stats {
+    fn lines(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("TOUCHDOWNS", self.touchdowns.to_string()),
+            ("PICKUPS", self.pickups.to_string()),
+            ("YARDS RUN", format!("{:.0}", self.yards)),
+            ("DIVES DODGED", self.dives_dodged.to_string()),
+            ("TIMES GRABBED", self.times_attached.to_string()),
+            ("ENEMIES COMBINED", self.enemies_bladed.to_string()),
+            ("CORN CUT WITH BALL", self.corn_harvested.to_string()),
+            ("TIME ALIVE", format_time(self.time_alive)),
+        ]
+    }
+}
+
+fn format_time(seconds: f32) -> String {
+    let seconds = seconds.max(0.0) as usize;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[derive(Default)]
+pub struct RunStats {
+    pub round: Stats,
+    pub run: Stats,
+}
+
+impl RunStats {
+    fn record(&mut self, update: impl Fn(&mut Stats)) {
+        update(&mut self.round);
+        update(&mut self.run);
+    }
+}
+
+fn record_events(
+    mut run_stats: ResMut<RunStats>,
+    mut stat_event_reader: EventReader<StatEvent>,
+    mut touchdown_event_reader: EventReader<game_state::TouchdownEvent>,
+    mut enemy_blade_event_reader: EventReader<enemy::EnemyBladeEvent>,
+    players: Query<&player::Player>,
+) {
+    let has_football = players.iter().any(|player| player.has_football);
+    for event in stat_event_reader.iter() {
+        match event {
+            StatEvent::Pickup => run_stats.record(|stats| stats.pickups += 1),
+            StatEvent::DiveDodged => run_stats.record(|stats| stats.dives_dodged += 1),
+            StatEvent::Attached => run_stats.record(|stats| stats.times_attached += 1),
+            StatEvent::CornHarvested if has_football => {
+                run_stats.record(|stats| stats.corn_harvested += 1)
+            }
+            StatEvent::CornHarvested => (),
+        }
+    }
+
+    for event in touchdown_event_reader.iter() {
+        // two-point tries are part of the touchdown before them
+        if event.football != football::FootballKind::Conversion {
+            run_stats.record(|stats| stats.touchdowns += 1);
+        }
+    }
+
+    for _ in enemy_blade_event_reader.iter() {
+        run_stats.record(|stats| stats.enemies_bladed += 1);
+    }
+}
+
+fn track_player(
+    mut run_stats: ResMut<RunStats>,
+    mut last_position: Local<Option<Vec3>>,
+    players: Query<(&player::Player, &Transform)>,
+    field: Res<field::FieldLayout>,
+    time: Res<Time>,
+) {
+    for (player, transform) in &players {
+        if player.is_dead {
+            *last_position = None;
+            continue;
+        }
+
+        let position = Vec3::new(transform.translation.x, 0.0, transform.translation.z);
+        let step = last_position
+            .map(|last| position.distance(last))
+            .filter(|step| *step < MAX_STEP)
+            .unwrap_or(0.0);
+        *last_position = Some(position);
+
+        let yards = field.to_yards(step);
+        let delta = time.delta_seconds();
+        run_stats.record(|stats| {
+            stats.yards += yards;
+            stats.time_alive += delta;
+        });
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    run_stats: Res<RunStats>,
+    game_state: Res<game_state::GameState>,
+    text_scaler: text_size::TextScaler,
+) {
+    // the game cameras are gone once the outro cutscene is over
+    commands
+        .spawn_bundle(Camera2dBundle::default())
+        .insert(CleanupMarker);
+
+    commands
+        .spawn_bundle(InputManagerBundle {
+            input_map: MenuAction::default_input_map(),
+            action_state: ActionState::default(),
+        })
+        .insert(CleanupMarker);
+
+    let style = |font_size: f32, color: Color| TextStyle {
+        font: game_assets.font.clone(),
+        font_size: text_scaler.scale(font_size),
+        color,
+    };
+    let table = run_stats
+        .round
+        .lines()
+        .iter()
+        .zip(run_stats.run.lines())
+        .map(|((label, round), (_, run))| format!("{:<20}{:>8}{:>8}\n", label, round, run))
+        .collect::<String>();
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::ColumnReverse,
+                ..Default::default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.75).into(),
+            ..Default::default()
+        })
+        .insert(CleanupMarker)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle::from_section(
+                format!("ROUND {} RESULTS", game_state.current_round + 1),
+                style(menus::DEFAULT_FONT_SIZE * 0.7, Color::WHITE),
+            ));
+            parent.spawn_bundle(TextBundle::from_section(
+                format!("{:<20}{:>8}{:>8}\n{}", "", "ROUND", "RUN", table),
+                style(menus::BUTTON_LABEL_FONT_SIZE, Color::WHITE),
+            ));
+            parent.spawn_bundle(TextBundle::from_section(
+                "PRESS SELECT TO CONTINUE",
+                style(menus::BUTTON_LABEL_FONT_SIZE, Color::rgb(1.0, 0.8, 0.2)),
+            ));
+        });
+}
+
+fn leave_results(
+    action_state: Query<&ActionState<MenuAction>>,
+    mut run_stats: ResMut<RunStats>,
+    mut assets_handler: asset_loading::AssetsHandler,
+    mut game_assets: ResMut<GameAssets>,
+    game_state: ResMut<game_state::GameState>,
+    mut audio: GameAudio,
+) {
+    let action_state = match action_state.get_single() {
+        Ok(action_state) => action_state,
+        Err(_) => return,
+    };
+    if !action_state.just_pressed(MenuAction::Select) {
+        return;
+    }
+
+    audio.play_sfx(&game_assets.blip);
+    // test plays and the last round end the run
+    let is_last_round = game_state.test_maze.is_some()
+        || assets_handler
+            .rounds
+            .get(game_state.current_round + 1)
+            .is_none();
+    if is_last_round {
+        *run_stats = RunStats::default();
+    } else {
+        run_stats.round = Stats::default();
+    }
+    assets_handler.load(AppState::LevelOver, &mut game_assets, &game_state);
+}
+
+fn handle_controllers(
+    controllers: Res<game_controller::GameController>,
+    mut players: Query<(Entity, &mut ActionState<MenuAction>)>,
+) {
+    for (_, mut action_state) in players.iter_mut() {
+        for (_, just_pressed) in controllers.just_pressed.iter() {
+            action_state.release(MenuAction::Select);
+
+            if just_pressed.contains(&game_controller::GameButton::ActionDown)
+                || just_pressed.contains(&game_controller::GameButton::Start)
+            {
+                action_state.press(MenuAction::Select);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_shows_minutes_and_seconds() {
+        assert_eq!(format_time(0.0), "0:00");
+        assert_eq!(format_time(59.9), "0:59");
+        assert_eq!(format_time(125.0), "2:05");
+    }
+
+    #[test]
+    fn records_go_to_the_round_and_the_run() {
+        let mut run_stats = RunStats {
+            run: Stats {
+                pickups: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        run_stats.record(|stats| stats.pickups += 1);
+
+        assert_eq!(run_stats.round.pickups, 1);
+        assert_eq!(run_stats.run.pickups, 4);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     asset_loading, assets::GameAssets, audio::GameAudio, banter, cleanup, cutscene,
-    game_controller, game_state, menus, shaders, ui::text_size, AppState,
+    game_controller, game_state, menus, rounds, shaders, ui::text_size, AppState,
 };
 use bevy::app::AppExit;
 use bevy::core_pipeline::clear_color::ClearColorConfig;
@@ -77,6 +77,7 @@ pub fn load(
     assets_handler.add_audio(&mut game_assets.titlescreen, "audio/football.ogg");
     assets_handler.add_audio(&mut game_assets.blip, "audio/blip.wav");
     assets_handler.add_font(&mut game_assets.font, "fonts/monogram.ttf");
+    assets_handler.add_round_definitions(&mut game_assets.rounds, "data/rounds.ron");
     assets_handler.add_material(
         &mut game_assets.title_screen_logo,
         "textures/logo.png",
@@ -94,9 +95,19 @@ fn setup(
     mut clear_color: ResMut<ClearColor>,
     mut banter_state: ResMut<banter::BanterState>,
     mut cutscene_state: ResMut<cutscene::CutsceneState>,
+    round_definitions: Res<Assets<rounds::RoundDefinitions>>,
+    mut rounds: ResMut<rounds::Rounds>,
     text_scaler: text_size::TextScaler,
 ) {
-    cutscene_state.init(cutscene::Cutscene::Intro);
+    if let Some(round_definitions) = round_definitions.get(&game_assets.rounds) {
+        rounds.set(round_definitions);
+    }
+    cutscene_state.init(
+        rounds
+            .get(0)
+            .map(|round| round.intro)
+            .unwrap_or(cutscene::Cutscene::Intro),
+    );
     banter_state.reset(&game_assets);
 
     clear_color.0 = Color::hex("00068a").unwrap();
@@ -211,7 +222,7 @@ fn setup(
     commands
         .spawn_bundle(NodeBundle {
             style: Style {
-                size: Size::new(Val::Percent(30.0), Val::Percent(25.0)),
+                size: Size::new(Val::Percent(30.0), Val::Percent(36.0)),
                 position_type: PositionType::Relative,
                 justify_content: JustifyContent::Center,
                 flex_direction: FlexDirection::ColumnReverse,
@@ -234,7 +245,7 @@ fn setup(
                     style: Style {
                         position_type: PositionType::Relative,
                         margin: UiRect::all(Val::Auto),
-                        size: Size::new(Val::Percent(100.0), Val::Percent(40.0)),
+                        size: Size::new(Val::Percent(100.0), Val::Percent(23.0)),
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
                         ..Default::default()
@@ -260,7 +271,63 @@ fn setup(
             parent
                 .spawn_bundle(ButtonBundle {
                     style: Style {
-                        size: Size::new(Val::Percent(100.0), Val::Percent(40.0)),
+                        size: Size::new(Val::Percent(100.0), Val::Percent(23.0)),
+                        margin: UiRect::all(Val::Auto),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        position_type: PositionType::Relative,
+                        ..Default::default()
+                    },
+                    color: menus::NORMAL_BUTTON.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::from_section(
+                            "High Scores",
+                            TextStyle {
+                                font: game_assets.font.clone(),
+                                font_size: text_scaler.scale(menus::BUTTON_LABEL_FONT_SIZE),
+                                color: Color::WHITE,
+                            },
+                        ),
+                        ..Default::default()
+                    });
+                })
+                .insert(CleanupMarker);
+
+            parent
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(23.0)),
+                        margin: UiRect::all(Val::Auto),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        position_type: PositionType::Relative,
+                        ..Default::default()
+                    },
+                    color: menus::NORMAL_BUTTON.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::from_section(
+                            "Maze Editor",
+                            TextStyle {
+                                font: game_assets.font.clone(),
+                                font_size: text_scaler.scale(menus::BUTTON_LABEL_FONT_SIZE),
+                                color: Color::WHITE,
+                            },
+                        ),
+                        ..Default::default()
+                    });
+                })
+                .insert(CleanupMarker);
+
+            parent
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(23.0)),
                         margin: UiRect::all(Val::Auto),
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
@@ -342,6 +409,14 @@ fn update_menu_buttons(
             assets_handler.load(AppState::Options, &mut game_assets, &mut game_state);
         }
         if *selected_button == 1 {
+            audio.play_sfx(&game_assets.blip);
+            assets_handler.load(AppState::HighScores, &mut game_assets, &mut game_state);
+        }
+        if *selected_button == 2 {
+            audio.play_sfx(&game_assets.blip);
+            assets_handler.load(AppState::Editor, &mut game_assets, &mut game_state);
+        }
+        if *selected_button == 3 {
             exit.send(AppExit);
         }
     }
@@ -0,0 +1,145 @@
+use crate::{
+    assets::GameAssets,
+    audio::GameAudio,
+    collision,
+    maze::{self, CornStalk},
+    particles,
+    spatial_grid::SpatialGrid,
+    AppState,
+};
+use bevy::prelude::*;
+use rand::Rng;
+
+/// Lets the player shove their way through corn walls. Pushing on a stalk wears it down until
+/// it's flattened, and flattened corn leaves a trail that enemies can pick up and follow.
+pub struct TramplePlugin;
+impl Plugin for TramplePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Trail>()
+            .add_event::<TrampleEvent>()
+            .add_system_set(SystemSet::on_exit(AppState::ResetInGame).with_system(clear_trail))
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(trample_corn)
+                    .with_system(fade_trail),
+            );
+    }
+}
+
+// how much of the player's movement is left while they're pushing into corn
+pub const TRAMPLE_SLOWDOWN: f32 = 0.15;
+// seconds of pushing it takes to flatten a stalk
+const STALK_TOUGHNESS: f32 = 0.6;
+// how long enemies can still follow flattened corn
+const TRAIL_TIME: f32 = 30.0;
+// how far away enemies can pick up a trail from
+const TRAIL_SCENT_DISTANCE: f32 = 5.0;
+
+/// Sent while something is pushing into corn at the position, amount is how long it pushed for.
+pub struct TrampleEvent {
+    pub position: Vec3,
+    pub direction: Vec3,
+    pub amount: f32,
+}
+
+#[derive(Component)]
+pub struct TrampledCorn {
+    damage: f32,
+}
+
+struct TrailPoint {
+    position: Vec3,
+    time_left: f32,
+}
+
+#[derive(Default)]
+pub struct Trail {
+    points: Vec<TrailPoint>,
+}
+
+impl Trail {
+    /// The freshest bit of trail near the position that isn't right on top of it.
+    pub fn scent_near(&self, position: Vec3) -> Option<Vec3> {
+        self.points
+            .iter()
+            .filter(|point| point.position.distance(position) <= TRAIL_SCENT_DISTANCE)
+            .max_by(|a, b| a.time_left.total_cmp(&b.time_left))
+            .map(|point| point.position)
+            .filter(|point| point.distance(position) > 0.5)
+    }
+}
+
+fn clear_trail(mut trail: ResMut<Trail>) {
+    trail.points.clear();
+}
+
+fn fade_trail(mut trail: ResMut<Trail>, time: Res<Time>) {
+    for point in trail.points.iter_mut() {
+        point.time_left -= time.delta_seconds();
+    }
+    trail.points.retain(|point| point.time_left > 0.0);
+}
+
+fn trample_corn(
+    mut commands: Commands,
+    mut trample_event_reader: EventReader<TrampleEvent>,
+    mut corns: Query<(&mut CornStalk, &Transform, Option<&TrampledCorn>)>,
+    spatial_grid: Res<SpatialGrid>,
+    mut trail: ResMut<Trail>,
+    game_assets: Res<GameAssets>,
+    mut audio: GameAudio,
+    mut sound_cooldown: Local<f32>,
+    mut spawn_particles_event_writer: EventWriter<particles::SpawnParticlesEvent>,
+    time: Res<Time>,
+) {
+    *sound_cooldown -= time.delta_seconds();
+    *sound_cooldown = sound_cooldown.clamp(-3.0, 3.0);
+
+    let mut rng = rand::thread_rng();
+    for event in trample_event_reader.iter() {
+        let touching = spatial_grid
+            .corn_near(
+                event.position,
+                maze::CORN_THICKNESS + collision::ACTOR_RADIUS,
+            )
+            .collect::<Vec<_>>();
+        for entity in touching {
+            let (mut corn, transform, trampled) = match corns.get_mut(entity) {
+                Ok(corn) => corn,
+                Err(_) => continue,
+            };
+            if corn.is_harvested || !corn.kind.is_trampleable() {
+                continue;
+            }
+
+            let damage = trampled.map_or(0.0, |trampled| trampled.damage) + event.amount;
+            if damage < STALK_TOUGHNESS {
+                commands.entity(entity).insert(TrampledCorn { damage });
+                continue;
+            }
+
+            corn.is_harvested = true;
+            commands
+                .entity(entity)
+                .remove::<TrampledCorn>()
+                .remove::<collision::Collidable>()
+                .insert(maze::ShrinkCorn {
+                    direction: event.direction,
+                    shrink_time: 1.0,
+                });
+            spawn_particles_event_writer.send(particles::SpawnParticlesEvent::new(
+                particles::ParticleKind::Chaff,
+                transform.translation + Vec3::Y,
+            ));
+            trail.points.push(TrailPoint {
+                position: transform.translation,
+                time_left: TRAIL_TIME,
+            });
+        }
+
+        if *sound_cooldown <= 0.0 {
+            audio.play_sfx_pitched(&game_assets.corn_harvest, rng.gen_range(1.4..1.8));
+            *sound_cooldown = 0.3;
+        }
+    }
+}